@@ -0,0 +1,74 @@
+//! erreport-macros
+//! ---
+//! Attribute macros for `erreport`, use them through the `macros` feature of `erreport` instead of depending on this crate.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_macro_input, Expr, ImplItemFn, Item};
+
+/// Rewrites every `expr?` in the function body to `expr.report()?`.
+///
+/// See `erreport::auto_report` for the documentation.
+#[proc_macro_attribute]
+pub fn auto_report(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "`auto_report` takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    // `ImplItemFn` also parses free functions, and keeps `self` receivers for methods
+    let mut function = parse_macro_input!(item as ImplItemFn);
+    InsertReport.visit_block_mut(&mut function.block);
+    quote!(#function).into()
+}
+
+/// Wraps the operand of every `?` which is not already reported.
+struct InsertReport;
+
+impl VisitMut for InsertReport {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            // A `?` in these returns from the closure or block, not from the function
+            Expr::Closure(_) | Expr::Async(_) | Expr::TryBlock(_) => {}
+            Expr::Try(try_expr) => {
+                self.visit_expr_mut(&mut try_expr.expr);
+                if !is_reported(&try_expr.expr) {
+                    let inner = &try_expr.expr;
+                    // The span of the `?` makes the frame point at the original line
+                    *try_expr.expr =
+                        syn::parse_quote_spanned!(try_expr.question_token.span=> (#inner).report());
+                }
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    // Nested functions and impls get their own `#[auto_report]` if they want it
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
+
+/// Whether the expression already converts to a `Report`, like `.report()`, `.report_with(..)` or `report!(..)`.
+///
+/// Macros are matched by the last segment of their path, so `erreport::report_with!(..)` counts too.
+fn is_reported(expr: &Expr) -> bool {
+    match expr {
+        Expr::MethodCall(call) => {
+            let method = call.method.to_string();
+            method == "report"
+                || method.starts_with("report_")
+                || method == "__report_in"
+                || method.starts_with("ok_or_report")
+        }
+        Expr::Macro(mac) => mac
+            .mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "report" || segment.ident == "report_with"),
+        Expr::Paren(paren) => is_reported(&paren.expr),
+        _ => false,
+    }
+}
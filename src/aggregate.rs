@@ -0,0 +1,55 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter, Result};
+
+/// A leaf error holding several independent errors, created by `aggregate!`.
+///
+/// It renders as `3 errors: [a; b; c]`, each error with its own `Display`, so nested reports show their whole chain.
+/// The aggregate is the leaf of the report built around it: `depth()` and `frames()` stop at it and don't walk into
+/// the inner errors. Its `source()` is the first error, so from the report that is `source().source()`,
+/// use `errors()` to reach the others.
+pub struct AggregateError {
+    errors: Vec<Box<dyn Error>>,
+}
+
+impl AggregateError {
+    pub fn new(errors: Vec<Box<dyn Error>>) -> Self {
+        AggregateError { errors }
+    }
+
+    /// The collected errors, in the order they were given
+    pub fn errors(&self) -> &[Box<dyn Error>] {
+        &self.errors
+    }
+}
+
+impl Display for AggregateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let count = self.errors.len();
+        write!(
+            f,
+            "{} {}: [",
+            count,
+            if count == 1 { "error" } else { "errors" }
+        )?;
+        for (i, err) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", err)?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl Debug for AggregateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_list().entries(&self.errors).finish()
+    }
+}
+
+impl Error for AggregateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.errors.first().map(|err| err.as_ref())
+    }
+}
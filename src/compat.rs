@@ -0,0 +1,118 @@
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter, Result};
+
+#[cfg(feature = "eyre")]
+use crate::Report;
+
+/// The leaf stored when an `anyhow::Error` is reported, since `anyhow::Error` doesn't implement `Error` itself.
+///
+/// Get the original error back with `report.source()` and `downcast_ref::<AnyhowError>()`:
+///
+/// ```
+/// # erreport::prepare!();
+/// use anyhow::Context;
+/// use erreport::AnyhowError;
+/// use std::error::Error;
+///
+/// let result: anyhow::Result<()> = Err(std::io::Error::other("no such file")).context("reading the config");
+/// let report = result.report().unwrap_err();
+/// assert!(report.to_string().ends_with(" -> reading the config"));
+///
+/// let leaf = report.source().unwrap().downcast_ref::<AnyhowError>().unwrap();
+/// assert_eq!(leaf.to_string(), "reading the config");
+/// assert_eq!(leaf.source().unwrap().to_string(), "no such file");
+/// assert!(leaf.0.downcast_ref::<std::io::Error>().is_some());
+/// ```
+#[cfg(feature = "anyhow")]
+pub struct AnyhowError(pub anyhow::Error);
+
+#[cfg(feature = "anyhow")]
+impl Debug for AnyhowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+#[cfg(feature = "anyhow")]
+impl Display for AnyhowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.0, f)
+    }
+}
+#[cfg(feature = "anyhow")]
+impl Error for AnyhowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// The leaf stored when an `eyre::Report` is reported, since `eyre::Report` doesn't implement `Error` itself.
+///
+/// The eyre report is kept whole, so its own chain of causes is still reachable through `source()`:
+///
+/// ```
+/// # erreport::prepare!();
+/// use erreport::EyreError;
+/// use eyre::WrapErr;
+/// use std::error::Error;
+///
+/// let result: eyre::Result<()> = Err(std::io::Error::other("no such file")).wrap_err("reading the config");
+/// let report = result.report().unwrap_err();
+/// assert!(report.to_string().ends_with(" -> reading the config"));
+///
+/// let leaf = report.source().unwrap().downcast_ref::<EyreError>().unwrap();
+/// assert_eq!(leaf.to_string(), "reading the config");
+/// assert_eq!(leaf.source().unwrap().to_string(), "no such file");
+/// assert!(leaf.0.downcast_ref::<std::io::Error>().is_some());
+/// ```
+#[cfg(feature = "eyre")]
+pub struct EyreError(pub eyre::Report);
+
+#[cfg(feature = "eyre")]
+impl Debug for EyreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+#[cfg(feature = "eyre")]
+impl Display for EyreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.0, f)
+    }
+}
+#[cfg(feature = "eyre")]
+impl Error for EyreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+#[cfg(feature = "eyre")]
+impl Report {
+    /// Converts into an `eyre::Report` whose message is the rendered chain, e.g. `.map_err(Report::into_eyre)?`.
+    ///
+    /// `Report` isn't `Send + Sync`, so it goes through a `ReportSnapshot`: the frames stay structured and can be read
+    /// back with `downcast_ref::<ReportSnapshot>()`, but the leaf error is flattened to its message. The other direction,
+    /// reporting an `eyre::Result`, keeps the eyre report whole as an `EyreError` leaf.
+    ///
+    /// This can't be a `From` impl, it would overlap eyre's blanket `From<E: Error + Send + Sync>`.
+    ///
+    /// ```
+    /// use erreport::{Report, ReportSnapshot};
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let rendered = report.to_string();
+    ///
+    /// let eyre = report.into_eyre();
+    /// assert_eq!(eyre.to_string(), rendered);
+    /// assert!(eyre.source().is_none());
+    ///
+    /// let snapshot = eyre.downcast_ref::<ReportSnapshot>().unwrap();
+    /// assert_eq!(snapshot.frames.len(), 2);
+    /// assert_eq!(snapshot.frames[1].pkg_name, "dep");
+    /// assert_eq!(snapshot.cause, "boom");
+    /// ```
+    pub fn into_eyre(self) -> eyre::Report {
+        eyre::Report::msg(self.snapshot())
+    }
+}
@@ -0,0 +1,63 @@
+use alloc::boxed::Box;
+use core::fmt::{Display, Formatter, Result};
+
+use crate::{display, Frame, Report};
+
+/// Lets `miette` render a `Report`: the code is `Report::code()` and the help tells where the error was raised
+/// and where it ended up, like `raised at {dep@1.2.0} src/lib.rs:7:17, reported at {app@0.1.0} src/main.rs:10:5 (3 frames)`.
+///
+/// The message is still the usual one-line chain, and the leaf error comes next as its `source()`.
+/// `Report` isn't `Send + Sync`, so it can't be turned into a `miette::Report`; render it with a handler directly,
+/// like `miette::GraphicalReportHandler::new().render_report(&mut out, &report)`.
+///
+/// ```
+/// use erreport::Report;
+/// use miette::Diagnostic;
+///
+/// let mut report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+/// report.extra_mut().code = Some("E_CONFIG");
+/// assert_eq!(report.help().unwrap().to_string(), "raised at {dep@1.2.0} src/lib.rs:7");
+///
+/// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+/// assert_eq!(Diagnostic::code(&report).unwrap().to_string(), "E_CONFIG");
+/// assert_eq!(
+///     report.help().unwrap().to_string(),
+///     "raised at {dep@1.2.0} src/lib.rs:7, reported at {app@0.1.0} src/main.rs:10 (2 frames)"
+/// );
+///
+/// let report = Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+/// assert!(Diagnostic::code(&report).is_none());
+/// ```
+impl miette::Diagnostic for Report {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Report::code(self).map(|code| Box::new(code) as Box<dyn Display>)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(Help(self)))
+    }
+}
+
+struct Help<'a>(&'a Report);
+
+impl Display for Help<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let outermost = self.0.entry_frame();
+        let innermost = self.0.innermost_frame();
+        f.write_str("raised at ")?;
+        write_frame(f, &innermost)?;
+        let depth = self.0.depth();
+        if depth > 1 {
+            f.write_str(", reported at ")?;
+            write_frame(f, &outermost)?;
+            write!(f, " ({} frames)", depth)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_frame(f: &mut Formatter<'_>, frame: &Frame<'_>) -> Result {
+    display::write_header(f, frame, &display::Style::DEFAULT, false)?;
+    f.write_str(" ")?;
+    display::write_location(f, frame, &display::Style::DEFAULT)
+}
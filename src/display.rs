@@ -0,0 +1,1492 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter, Result, Write};
+use core::ptr;
+
+use crate::{Frame, Report};
+
+/// A `Display` adapter rendering a `Report` with non-default options, created by methods like `Report::display_with`
+pub struct ReportDisplay<'a> {
+    report: &'a Report,
+    style: Style<'a>,
+}
+
+impl Display for ReportDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let leaf = |f: &mut dyn Write| {
+            #[cfg(feature = "std")]
+            if self.style.io_kind && self.report.message().is_none() {
+                if let Some(err) = self.report.downcast_source_ref::<std::io::Error>() {
+                    return write!(f, "{:?}: {}", err.kind(), err);
+                }
+            }
+            write!(f, "{}", Leaf(self.report))?;
+            match self.report.leaf_type() {
+                Some(leaf_type) if self.style.leaf_type => {
+                    f.write_str(" [")?;
+                    write_short_type(f, leaf_type)?;
+                    f.write_char(']')
+                }
+                _ => Ok(()),
+            }?;
+            if self.style.causes {
+                write_causes(f, self.report.root_cause())?;
+            }
+            Ok(())
+        };
+        write_chain(f, self.report.frames(), &self.style, |f| {
+            if self.style.sanitize {
+                leaf(&mut Sanitized(f))
+            } else {
+                leaf(f)
+            }
+        })
+    }
+}
+
+/// The `Display` of the leaf error of a report, or its `Report::message` when it has one
+pub(crate) struct Leaf<'a>(pub(crate) &'a Report);
+
+impl Display for Leaf<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.0.message() {
+            Some(message) => f.write_str(message),
+            None => {
+                #[cfg(feature = "capture-leaf")]
+                if let Some(display) = &self.0.innermost().extra().leaf_display {
+                    return f.write_str(display);
+                }
+                write!(f, "{}", self.0.root_cause())
+            }
+        }
+    }
+}
+
+/// Writes the `file:line` of every frame for `Report::path_string`
+pub(crate) struct PathOnly<'a>(pub(crate) &'a Report);
+
+impl Display for PathOnly<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (i, frame) in self.0.frames().enumerate() {
+            if i > 0 {
+                f.write_str(Style::DEFAULT.separator)?;
+            }
+            write_path(f, frame.file)?;
+            write!(f, ":{}", frame.line)?;
+        }
+        Ok(())
+    }
+}
+
+/// What the default `Display` of a `Report` renders, chosen by the `ERREPORT_VERBOSITY` environment variable
+/// with the `env-verbosity` feature. See `Report::verbosity`.
+///
+/// ```
+/// use erreport::{Report, Verbosity};
+///
+/// std::env::set_var("ERREPORT_VERBOSITY", "path-only");
+/// let report = Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+/// assert_eq!(report.to_string(), "src/main.rs:10");
+/// assert_eq!(Report::verbosity(), Verbosity::PathOnly);
+/// ```
+///
+/// The display adapters, like `display_with` or `tree`, always render in full.
+#[cfg(feature = "env-verbosity")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    /// The whole chain and the leaf error, when the variable is unset or has any other value
+    Full,
+    /// `path-only`: only the locations, like `Report::path_string`
+    PathOnly,
+    /// `leaf-only`: only the leaf error, or the `Report::message`
+    LeafOnly,
+}
+
+/// The `Debug` of the leaf error of a report, which ignores `Report::message`
+pub(crate) struct LeafDebug<'a>(pub(crate) &'a Report);
+
+impl Debug for LeafDebug<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        #[cfg(feature = "capture-leaf")]
+        if let Some(debug) = &self.0.innermost().extra().leaf_debug {
+            return f.write_str(debug);
+        }
+        write!(f, "{:?}", self.0.root_cause())
+    }
+}
+
+#[cfg(feature = "std")]
+static BUILD_INFO: std::sync::RwLock<Option<&'static str>> = std::sync::RwLock::new(None);
+
+impl Report {
+    /// Sets a build identifier, like a git sha, shown in the header of the outermost frame: `{app@0.1.0 build=abc1234}`.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// Report::set_build_info("abc1234");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, Report::new("app", "0.1.0", "src/lib.rs", 7, "boom"));
+    /// assert_eq!(report.to_string(), "{app@0.1.0 build=abc1234} src/main.rs:10 -> src/lib.rs:7 -> boom");
+    /// assert_eq!(Report::build_info(), Some("abc1234"));
+    /// ```
+    ///
+    /// It is process-global and meant to be called once at startup, e.g. with `env!("GIT_SHA")` set by a build script.
+    /// A later call replaces it.
+    #[cfg(feature = "std")]
+    pub fn set_build_info(info: &'static str) {
+        *BUILD_INFO
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(info);
+    }
+
+    /// The build identifier given to `set_build_info`, if any
+    #[cfg(feature = "std")]
+    pub fn build_info() -> Option<&'static str> {
+        *BUILD_INFO
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// The operating system the program was built for, like `linux` or `windows`, see `std::env::consts::OS`
+    #[cfg(feature = "std")]
+    pub fn target_os() -> &'static str {
+        std::env::consts::OS
+    }
+
+    /// The CPU architecture the program was built for, like `x86_64` or `aarch64`, see `std::env::consts::ARCH`
+    #[cfg(feature = "std")]
+    pub fn target_arch() -> &'static str {
+        std::env::consts::ARCH
+    }
+
+    /// Only the `file:line` of every frame, outermost first and joined by `" -> "`, without the `{pkg@ver}` headers,
+    /// columns, contexts or the leaf error. Reports which went through the same lines give the same string,
+    /// which makes it a readable grouping key next to `fingerprint`.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src\\parse.rs", 3, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(report.path_string(), "src/main.rs:10 -> src/parse.rs:3");
+    /// ```
+    pub fn path_string(&self) -> String {
+        PathOnly(self).to_string()
+    }
+
+    /// What the default `Display` renders, from the `ERREPORT_VERBOSITY` environment variable.
+    ///
+    /// The variable is read the first time this is called, usually by the first `Display`, and the result is cached
+    /// for the rest of the process: changing it later has no effect.
+    #[cfg(feature = "env-verbosity")]
+    pub fn verbosity() -> Verbosity {
+        static VERBOSITY: std::sync::OnceLock<Verbosity> = std::sync::OnceLock::new();
+
+        *VERBOSITY.get_or_init(|| match std::env::var("ERREPORT_VERBOSITY").as_deref() {
+            Ok("path-only") => Verbosity::PathOnly,
+            Ok("leaf-only") => Verbosity::LeafOnly,
+            _ => Verbosity::Full,
+        })
+    }
+
+    /// Renders the chain, then a `caused by:` line for every error of the leaf's own `source()` chain,
+    /// like the `io::Error` inside a `thiserror` error. The default `Display` stops at the leaf.
+    ///
+    /// ```
+    /// use erreport::Report;
+    /// use std::error::Error;
+    /// use std::fmt;
+    ///
+    /// #[derive(Debug)]
+    /// struct LoadError(std::io::Error);
+    ///
+    /// impl fmt::Display for LoadError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("failed to load the config")
+    ///     }
+    /// }
+    ///
+    /// impl Error for LoadError {
+    ///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+    ///         Some(&self.0)
+    ///     }
+    /// }
+    ///
+    /// let leaf = LoadError(std::io::Error::other("disk on fire"));
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, leaf);
+    /// assert_eq!(
+    ///     report.display_full_causes().to_string(),
+    ///     "{app@0.1.0} src/main.rs:10 -> failed to load the config\ncaused by: disk on fire"
+    /// );
+    /// ```
+    ///
+    /// The walk stops at the first error it already went through, so a `source()` which loops back still terminates:
+    ///
+    /// ```
+    /// use erreport::Report;
+    /// use std::error::Error;
+    /// use std::fmt;
+    ///
+    /// #[derive(Debug)]
+    /// struct Loop;
+    ///
+    /// impl fmt::Display for Loop {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("loop")
+    ///     }
+    /// }
+    ///
+    /// impl Error for Loop {
+    ///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+    ///         Some(self)
+    ///     }
+    /// }
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, Loop);
+    /// assert_eq!(
+    ///     report.display_full_causes().to_string(),
+    ///     "{app@0.1.0} src/main.rs:10 -> loop\ncaused by: ... (cycle)"
+    /// );
+    /// ```
+    pub fn display_full_causes(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.causes = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with the control characters of the leaf error escaped, so an error echoing untrusted input
+    /// can't break the line apart or inject terminal escapes into a log. `\n`, `\r` and `\t` are written as those two
+    /// characters, and any other control character as `\u{..}`:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, "bad name:\n\x1b[31mroot\x1b[0m");
+    /// assert_eq!(
+    ///     report.display_sanitized().to_string(),
+    ///     r"{app@0.1.0} src/main.rs:10 -> bad name:\n\u{1b}[31mroot\u{1b}[0m"
+    /// );
+    /// ```
+    ///
+    /// Contexts and `meta` values come from your own code and are written as they are.
+    pub fn display_sanitized(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.sanitize = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with `{pkg}` headers instead of `{pkg@ver}`, for snapshot tests which shouldn't break on a version bump
+    /// or shorter log lines.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(report.display_no_version().to_string(), "{app} src/main.rs:10 -> {dep} src/lib.rs:7 -> boom");
+    /// assert_eq!(report.frames().nth(1).unwrap().pkg_version, "1.2.0");
+    /// ```
+    ///
+    /// A new header still starts wherever the version changes, as frames from two versions of a crate are different packages.
+    pub fn display_no_version(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.no_version = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with the package names and versions of the headers percent-encoded, so a name like `my app}`
+    /// can't break a log parser splitting on spaces, braces or `@`. Everything but ASCII letters, digits, `-`, `_`, `.`
+    /// and `+` is encoded, which leaves every name and version crates.io accepts unchanged.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("my app}", "1.0.0@git", "src/main.rs", 10, "boom");
+    /// assert_eq!(report.to_string(), "{my app}@1.0.0@git} src/main.rs:10 -> boom");
+    /// assert_eq!(
+    ///     report.display_escaped_packages().to_string(),
+    ///     "{my%20app%7D@1.0.0%40git} src/main.rs:10 -> boom"
+    /// );
+    /// ```
+    ///
+    /// The default `Display` writes `pkg_name` and `pkg_version` exactly as `CARGO_PKG_NAME` and `CARGO_PKG_VERSION` gave them.
+    pub fn display_escaped_packages(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.escape_packages = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with every frame prefixed by its index, starting at the outermost frame,
+    /// to refer to frames by number. The indices match the positions in `frames()` and `to_frames_vec()`.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/c.rs", 3, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/b.rs", 2, report);
+    /// let report = Report::new("app", "0.1.0", "src/a.rs", 1, report);
+    /// assert_eq!(
+    ///     report.display_indexed().to_string(),
+    ///     "[0] {app@0.1.0} src/a.rs:1 -> [1] src/b.rs:2 -> [2] {dep@1.2.0} src/c.rs:3 -> boom"
+    /// );
+    /// ```
+    pub fn display_indexed(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.index = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with the short type name of the leaf error after it, like `... -> invalid digit found in string [ParseIntError]`.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let report = "x".parse::<u8>().report().report().unwrap_err();
+    /// assert!(report.display_with_type().to_string().ends_with(" -> invalid digit found in string [ParseIntError]"));
+    /// assert_eq!(report.leaf_type(), Some("core::num::error::ParseIntError"));
+    /// ```
+    ///
+    /// The type is recorded when the error is first reported, `leaf_type()` gives its full path.
+    /// Nothing is added when it is unknown, like for a leaf wrapped by `Report::new`.
+    pub fn display_with_type(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.leaf_type = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with the platform in the header of the outermost frame, like `{app@0.1.0 os=linux arch=x86_64}`,
+    /// for cross-platform bug reports. The default `Display` leaves it out.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_with_platform().to_string(),
+    ///     format!(
+    ///         "{{app@0.1.0 os={} arch={}}} src/main.rs:10 -> {{dep@1.2.0}} src/lib.rs:7 -> boom",
+    ///         Report::target_os(),
+    ///         Report::target_arch()
+    ///     )
+    /// );
+    /// ```
+    ///
+    /// A full target triple can be shown with `set_build_info`, from a `TARGET` variable set by a build script.
+    #[cfg(feature = "std")]
+    pub fn display_with_platform(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.platform = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Streams the `Display` rendering, or the `Debug` one if `debug` is set, into `w`, without building a `String` first
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, w: &mut impl std::io::Write, debug: bool) -> std::io::Result<()> {
+        if debug {
+            write!(w, "{:?}", self)
+        } else {
+            write!(w, "{}", self)
+        }
+    }
+
+    /// Writes the `Display` rendering and a newline to stderr, for the `Err` branch of a CLI's `main`.
+    ///
+    /// Stderr stays locked for the whole line so it doesn't interleave with other threads.
+    /// Unlike `eprintln!`, a failed write is ignored instead of panicking.
+    #[cfg(feature = "std")]
+    pub fn eprint(&self) {
+        use std::io::Write as _;
+
+        let _ = writeln!(std::io::stderr().lock(), "{}", self);
+    }
+
+    /// Same as `eprint`, with the `Debug` rendering
+    #[cfg(feature = "std")]
+    pub fn eprint_debug(&self) {
+        use std::io::Write as _;
+
+        let _ = writeln!(std::io::stderr().lock(), "{:?}", self);
+    }
+
+    /// Renders the chain with `separator` between frames instead of `" -> "`.
+    ///
+    /// An empty separator falls back to a single space so frames don't run into each other.
+    /// The `{pkg@ver}` header is still only written on the first frame of each package.
+    pub fn display_with<'a>(&'a self, separator: &'a str) -> ReportDisplay<'a> {
+        let mut style = Style::DEFAULT;
+        style.separator = match separator {
+            "" => " ",
+            separator => separator,
+        };
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with each frame prefixed by its timestamp, as `[seconds.millis since the Unix epoch]`:
+    ///
+    /// ```
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    /// let rendered = report.display_with_time().to_string();
+    /// let (time, rest) = rendered.strip_prefix('[').unwrap().split_once("] ").unwrap();
+    /// let (secs, millis) = time.split_once('.').unwrap();
+    /// assert!(secs.parse::<u64>().unwrap() > 1_600_000_000);
+    /// assert_eq!(millis.len(), 3);
+    /// assert_eq!(rest, "{app@0.1.0} src/main.rs:10 -> boom");
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn display_with_time(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.time = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain with each frame prefixed by the thread which created it, like `[worker-3] src/lib.rs:42:5`.
+    /// A thread without a name shows its `ThreadId`:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    /// assert_eq!(report.display_with_thread().to_string(), "[main] {app@0.1.0} src/main.rs:10 -> boom");
+    ///
+    /// let rendered = std::thread::spawn(|| {
+    ///     let report = Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    ///     report.display_with_thread().to_string()
+    /// });
+    /// assert!(rendered.join().unwrap().starts_with("[ThreadId("));
+    /// ```
+    pub fn display_with_thread(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.thread = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders every run of frames from the same package on its own line, under a single `{pkg@ver}` header.
+    ///
+    /// A package which shows up again further down the chain starts a new line, and the leaf error ends the last one:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/config.rs", 20, "invalid digit found in string");
+    /// let report = Report::new("dep", "1.2.0", "src/parse.rs", 3, report);
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, report);
+    /// let report = Report::new("app", "0.1.0", "src/db.rs", 42, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_by_package().to_string(),
+    ///     "{app@0.1.0} src/main.rs:10 -> src/db.rs:42\n\
+    ///      {dep@1.2.0} src/lib.rs:7 -> src/parse.rs:3\n\
+    ///      {app@0.1.0} src/config.rs:20 -> invalid digit found in string"
+    /// );
+    /// ```
+    pub fn display_by_package(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.layout = Layout::Packages;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders only the frames whose `(pkg_name, pkg_version)` pass `predicate`, always ending with the leaf error.
+    ///
+    /// Frames which are filtered out are not dropped silently: each run of them from one package is
+    /// collapsed into a single `... (N frames in pkg@ver) ...` marker, so it stays visible that the error
+    /// went through other crates.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// fn dep() -> Report<u8> {
+    ///     let mut report = "x".parse::<u8>().report().unwrap_err();
+    ///     report.pkg_name = "dep";
+    ///     Err(report)
+    /// }
+    ///
+    /// let report = dep().report().unwrap_err();
+    /// let rendered = report.display_filtered(|pkg_name, _| pkg_name != "dep").to_string();
+    /// assert!(rendered.starts_with("{erreport@"));
+    /// assert!(rendered.ends_with(" -> ... (1 frame in dep@0.3.0) ... -> invalid digit found in string"));
+    /// ```
+    pub fn display_filtered<F>(&self, predicate: F) -> FilteredDisplay<'_, F>
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        FilteredDisplay {
+            report: self,
+            predicate,
+        }
+    }
+
+    /// Renders at most `max_depth` frames, then a `... (truncated, N more)` marker and the leaf error.
+    ///
+    /// Use it to keep log lines or UI fields bounded, the default `Display` renders every frame.
+    ///
+    /// ```
+    /// let mut report = erreport::Report::new("app", "0.1.0", "src/main.rs", 1, "boom");
+    /// for line in 2..=50 {
+    ///     report = erreport::Report::new("app", "0.1.0", "src/main.rs", line, report);
+    /// }
+    /// assert_eq!(
+    ///     report.display_max_depth(5).to_string(),
+    ///     "{app@0.1.0} src/main.rs:50 -> src/main.rs:49 -> src/main.rs:48 -> src/main.rs:47 \
+    ///      -> src/main.rs:46 -> ... (truncated, 45 more) -> boom"
+    /// );
+    /// ```
+    pub fn display_max_depth(&self, max_depth: usize) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.max_depth = max_depth;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders only the `n` innermost frames, the ones closest to where the error happened, after a `...` marker.
+    ///
+    /// The first frame shown always gets its `{pkg@ver}` header:
+    ///
+    /// ```
+    /// let mut report = erreport::Report::new("dep", "1.2.0", "src/lib.rs", 1, "boom");
+    /// for line in 2..=10 {
+    ///     report = erreport::Report::new("app", "0.1.0", "src/main.rs", line, report);
+    /// }
+    /// assert_eq!(
+    ///     report.display_tail(3).to_string(),
+    ///     "... -> {app@0.1.0} src/main.rs:3 -> src/main.rs:2 -> {dep@1.2.0} src/lib.rs:1 -> boom"
+    /// );
+    /// assert_eq!(report.display_tail(10).to_string(), report.to_string());
+    /// ```
+    ///
+    /// It is the mirror of `display_max_depth`, which keeps the outermost frames instead.
+    pub fn display_tail(&self, n: usize) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.tail = n;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders every file as the 8 hex digits of its FNV-1a hash, like `{app@0.1.0} 1660407c:42:5 -> ...` for `src/main.rs`.
+    ///
+    /// The hash only depends on the path, so recurring errors can be correlated without showing the directory layout.
+    /// `\` separators are hashed as `/`, so a path hashes the same on every platform:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src\\lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(report.display_hashed_paths().to_string(), "{app@0.1.0} 1660407c:10 -> {dep@1.2.0} b37c6efa:7 -> boom");
+    /// ```
+    pub fn display_hashed_paths(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.hash_paths = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders every frame after the first on its own line, starting with an indented `->`:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "invalid config:\nmissing key `port`");
+    /// let report = Report::new("app", "0.1.0", "src/db.rs", 42, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_multiline().to_string(),
+    ///     "{app@0.1.0} src/main.rs:10\n\
+    ///      \x20 -> src/db.rs:42\n\
+    ///      \x20 -> {dep@1.2.0} src/lib.rs:7\n\
+    ///      \x20 -> invalid config:\n\
+    ///      \x20    missing key `port`"
+    /// );
+    /// ```
+    ///
+    /// Lines after the first in the leaf error line up under its start. The default `Display` stays on one line.
+    pub fn display_multiline(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.separator = "\n  -> ";
+        style.leaf_indent = "     ";
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain the other way around, from the leaf error to the outermost frame, joined by `" <- "`.
+    ///
+    /// It mirrors the default rendering, every frame keeps the `{pkg@ver}` header it has there:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("dep", "1.2.0", "src/api.rs", 3, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_reversed().to_string(),
+    ///     "boom <- src/lib.rs:7 <- {dep@1.2.0} src/api.rs:3 <- {app@0.1.0} src/main.rs:10"
+    /// );
+    ///
+    /// let forward = report.to_string();
+    /// let mut mirrored: Vec<&str> = forward.split(" -> ").collect();
+    /// mirrored.reverse();
+    /// assert_eq!(report.display_reversed().to_string(), mirrored.join(" <- "));
+    /// ```
+    pub fn display_reversed(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.separator = " <- ";
+        style.layout = Layout::Reversed;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders a `std::io::Error` leaf with its `ErrorKind` first, like `NotFound: entity not found`,
+    /// as its own message is often too terse to tell what happened. Other leaf errors render as usual.
+    ///
+    /// ```
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, Error::from(ErrorKind::NotFound));
+    /// assert_eq!(report.display_io_kind().to_string(), "{app@0.1.0} src/main.rs:10 -> NotFound: entity not found");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn display_io_kind(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.io_kind = true;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Whether the colored adapters, like `display_colored`, use colors. It is checked every time they are called:
+    /// * never when the `NO_COLOR` environment variable is set to a non-empty value,
+    /// * otherwise always when `CLICOLOR_FORCE` is set to a value other than empty or `0`,
+    /// * otherwise only when stderr is a terminal.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// std::env::set_var("NO_COLOR", "1");
+    /// std::env::set_var("CLICOLOR_FORCE", "1");
+    /// assert!(!Report::should_colorize());
+    ///
+    /// std::env::remove_var("NO_COLOR");
+    /// assert!(Report::should_colorize());
+    ///
+    /// std::env::set_var("NO_COLOR", "");
+    /// std::env::set_var("CLICOLOR_FORCE", "0");
+    /// assert_eq!(Report::should_colorize(), std::io::IsTerminal::is_terminal(&std::io::stderr()));
+    /// ```
+    #[cfg(feature = "color")]
+    pub fn should_colorize() -> bool {
+        use std::io::IsTerminal;
+
+        let set = |name: &str, off: &str| {
+            std::env::var_os(name).is_some_and(|value| !value.is_empty() && value != off)
+        };
+        if set("NO_COLOR", "") {
+            return false;
+        }
+        set("CLICOLOR_FORCE", "0") || std::io::stderr().is_terminal()
+    }
+
+    /// Renders the chain with ANSI colors for a terminal: packages in bold, locations dimmed and the leaf error in red.
+    ///
+    /// Falls back to the plain rendering unless `should_colorize()`. The default `Display` is never colored.
+    ///
+    /// ```
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    ///
+    /// std::env::remove_var("NO_COLOR");
+    /// std::env::set_var("CLICOLOR_FORCE", "1");
+    /// assert_eq!(
+    ///     report.display_colored().to_string(),
+    ///     "\x1b[1m{app@0.1.0}\x1b[0m \x1b[2msrc/main.rs:10\x1b[0m -> \x1b[31mboom\x1b[0m"
+    /// );
+    ///
+    /// std::env::set_var("NO_COLOR", "1");
+    /// assert_eq!(report.display_colored().to_string(), report.to_string());
+    /// ```
+    #[cfg(feature = "color")]
+    pub fn display_colored(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.color = Report::should_colorize();
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain as sections, one per run of frames from the same package: a `{pkg@ver}` line,
+    /// then the frames of the run indented under it. The leaf error ends the last section.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("rustls", "0.23.0", "src/conn.rs", 7, "bad certificate");
+    /// let report = Report::new("hyper", "1.4.0", "src/client.rs", 20, report);
+    /// let report = Report::new("hyper", "1.4.0", "src/conn.rs", 9, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_grouped().to_string(),
+    ///     "{app@0.1.0}\n  src/main.rs:10\n\
+    ///      {hyper@1.4.0}\n  src/conn.rs:9\n  src/client.rs:20\n\
+    ///      {rustls@0.23.0}\n  src/conn.rs:7\n  bad certificate"
+    /// );
+    /// ```
+    ///
+    /// Unlike `tree()`, the frames of a section are all at the same depth.
+    pub fn display_grouped(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.layout = Layout::Grouped;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain as an indented tree with one frame per line, for reading long chains.
+    ///
+    /// Every run of frames from the same package becomes a branch starting with its `{pkg@ver}` header:
+    ///
+    /// ```text
+    /// ├─ {app@0.1.0} src/main.rs:10:5
+    /// │  └─ src/db.rs:42:9
+    /// └─ {dep@1.2.0} src/lib.rs:7:17
+    ///    └─ invalid digit found in string
+    /// ```
+    ///
+    /// The lines of a multi-line leaf error are aligned under its first one:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "bad config\nline 3: unknown key");
+    /// let report = Report::new("app", "0.1.0", "src/db.rs", 42, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.tree().to_string(),
+    ///     "├─ {app@0.1.0} src/main.rs:10\n\
+    ///      │  └─ src/db.rs:42\n\
+    ///      └─ {dep@1.2.0} src/lib.rs:7\n   └─ bad config\n      line 3: unknown key"
+    /// );
+    /// ```
+    pub fn tree(&self) -> ReportDisplay<'_> {
+        let mut style = Style::DEFAULT;
+        style.layout = Layout::Tree;
+        ReportDisplay {
+            report: self,
+            style,
+        }
+    }
+
+    /// Renders the chain like `Display`, soft-wrapped to lines of at most `width` characters for a terminal.
+    ///
+    /// Lines break before a `->` where possible, continuation lines start with an indented `->` like `display_multiline`:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "invalid digit found in string");
+    /// let report = Report::new("dep", "1.2.0", "src/api.rs", 3, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.display_wrapped(50).to_string(),
+    ///     "{app@0.1.0} src/main.rs:10\n  -> {dep@1.2.0} src/api.rs:3 -> src/lib.rs:7\n  -> invalid digit found in string"
+    /// );
+    /// assert_eq!(report.display_wrapped(0).to_string().lines().next(), Some("{app@0.1.0}"));
+    /// ```
+    ///
+    /// A frame or leaf error which doesn't fit on a line of its own is broken between words instead,
+    /// and a single word longer than `width` overflows its line. Only the `->` between frames start a continuation
+    /// line, not one inside a context or message:
+    ///
+    /// ```
+    /// let mut report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    /// report.extra_mut().context = Some("a -> b".to_string());
+    /// assert_eq!(report.display_wrapped(30).to_string(), "{app@0.1.0} src/main.rs:10 (a\n     -> b) -> boom");
+    /// assert_eq!(report.display_wrapped(40).to_string(), "{app@0.1.0} src/main.rs:10 (a -> b)\n  -> boom");
+    /// ```
+    ///
+    /// Like the other display adapters it always renders in full, whatever `ERREPORT_VERBOSITY` says.
+    pub fn display_wrapped(&self, width: usize) -> WrappedDisplay<'_> {
+        WrappedDisplay {
+            report: self,
+            width,
+        }
+    }
+}
+
+/// A `Display` adapter soft-wrapping the rendering of a `Report`, created by `Report::display_wrapped`
+pub struct WrappedDisplay<'a> {
+    report: &'a Report,
+    width: usize,
+}
+
+impl Display for WrappedDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        const ARROW: &str = " -> ";
+        const CONTINUATION: &str = "  -> ";
+        const INDENT: &str = "     ";
+
+        // One segment per frame and one for the leaf, so a `->` inside a context or message is not a boundary
+        let mut prev: Option<Frame<'_>> = None;
+        let mut segments: Vec<String> = Vec::new();
+        for frame in self.report.frames() {
+            let header = !prev.is_some_and(|prev| prev.same_package(&frame));
+            segments.push(
+                FrameSegment {
+                    frame: &frame,
+                    header,
+                    top: prev.is_none(),
+                }
+                .to_string(),
+            );
+            prev = Some(frame);
+        }
+        segments.push(Leaf(self.report).to_string());
+
+        let len = |s: &str| s.chars().count();
+        let mut column = 0;
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                if column + len(ARROW) + len(segment) <= self.width {
+                    f.write_str(ARROW)?;
+                    column += len(ARROW);
+                } else {
+                    f.write_char('\n')?;
+                    f.write_str(CONTINUATION)?;
+                    column = len(CONTINUATION);
+                }
+            }
+            for (j, word) in segment.split(' ').enumerate() {
+                if j > 0 {
+                    if column + 1 + len(word) <= self.width {
+                        f.write_char(' ')?;
+                        column += 1;
+                    } else {
+                        f.write_char('\n')?;
+                        f.write_str(INDENT)?;
+                        column = len(INDENT);
+                    }
+                }
+                f.write_str(word)?;
+                column = match word.rsplit_once('\n') {
+                    Some((_, last)) => len(last),
+                    None => column + len(word),
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A frame as `display_wrapped` lays it out: its `{pkg@ver}` header if it starts a package, and its location
+struct FrameSegment<'a, 'b> {
+    frame: &'a Frame<'b>,
+    header: bool,
+    top: bool,
+}
+
+impl Display for FrameSegment<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let style = Style::DEFAULT;
+        if self.header {
+            write_header(f, self.frame, &style, self.top)?;
+            f.write_char(' ')?;
+        }
+        write_location(f, self.frame, &style)
+    }
+}
+
+/// A `Display` adapter rendering only some of the frames of a `Report`, created by `Report::display_filtered`
+pub struct FilteredDisplay<'a, F> {
+    report: &'a Report,
+    predicate: F,
+}
+
+impl<F: Fn(&str, &str) -> bool> Display for FilteredDisplay<'_, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let style = Style::DEFAULT;
+        let mut prev: Option<Frame<'_>> = None;
+        let mut omitted = 0;
+        let mut top = true;
+        for frame in self.report.frames() {
+            let keep = (self.predicate)(frame.pkg_name, frame.pkg_version);
+            if let (Some(last), true) = (prev, omitted > 0) {
+                if keep || !last.same_package(&frame) {
+                    write_omitted(f, &last, omitted)?;
+                    f.write_str(style.separator)?;
+                    omitted = 0;
+                }
+            }
+            if keep {
+                if !prev.is_some_and(|prev| prev.same_package(&frame)) {
+                    write_header(f, &frame, &style, top)?;
+                    f.write_char(' ')?;
+                    top = false;
+                }
+                write_location(f, &frame, &style)?;
+                f.write_str(style.separator)?;
+            } else {
+                omitted += 1;
+            }
+            prev = Some(frame);
+        }
+        if let (Some(last), true) = (prev, omitted > 0) {
+            write_omitted(f, &last, omitted)?;
+            f.write_str(style.separator)?;
+        }
+        write!(f, "{}", Leaf(self.report))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Style<'a> {
+    pub(crate) separator: &'a str,
+    #[cfg(feature = "time")]
+    pub(crate) time: bool,
+    pub(crate) thread: bool,
+    pub(crate) layout: Layout,
+    /// Frames past this many are replaced by a `... (truncated, N more)` marker, only in the `Line` layout
+    pub(crate) max_depth: usize,
+    /// Only this many frames closest to the leaf are shown, after a `...` marker, only in the `Line` layout
+    pub(crate) tail: usize,
+    pub(crate) hash_paths: bool,
+    /// Written after every line break inside the leaf error, only in the `Line` layout
+    pub(crate) leaf_indent: &'a str,
+    /// Packages in bold, locations dimmed and the leaf error in red, with ANSI escapes
+    pub(crate) color: bool,
+    /// A `std::io::Error` leaf is prefixed by its `ErrorKind`
+    #[cfg(feature = "std")]
+    pub(crate) io_kind: bool,
+    /// The `source()` chain of the leaf error follows it, one `caused by:` line each
+    pub(crate) causes: bool,
+    /// Control characters in the leaf error are escaped, see `Report::display_sanitized`
+    pub(crate) sanitize: bool,
+    /// Headers are `{pkg}` instead of `{pkg@ver}`
+    pub(crate) no_version: bool,
+    /// The package name and version in headers are percent-encoded, see `Report::display_escaped_packages`
+    pub(crate) escape_packages: bool,
+    /// Every frame is prefixed by its position in the chain, like `[0] `, only in the `Line` layout
+    pub(crate) index: bool,
+    /// The leaf error is followed by the short name of its type, like `[ParseIntError]`
+    pub(crate) leaf_type: bool,
+    /// The header of the outermost frame also shows `os=.. arch=..`
+    #[cfg(feature = "std")]
+    pub(crate) platform: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// Every frame on one line, joined by the separator
+    Line,
+    /// One frame per line, drawn as a tree with a branch per package
+    Tree,
+    /// One line per run of frames from the same package
+    Packages,
+    /// The leaf first and the outermost frame last, each frame keeping the header it has in `Line`
+    Reversed,
+    /// A `{pkg@ver}` line per run of frames from the same package, with the frames indented under it
+    Grouped,
+}
+
+impl Style<'static> {
+    pub(crate) const DEFAULT: Self = Self {
+        separator: " -> ",
+        #[cfg(feature = "time")]
+        time: false,
+        thread: false,
+        layout: Layout::Line,
+        max_depth: usize::MAX,
+        tail: usize::MAX,
+        hash_paths: false,
+        leaf_indent: "",
+        color: false,
+        #[cfg(feature = "std")]
+        io_kind: false,
+        causes: false,
+        sanitize: false,
+        no_version: false,
+        escape_packages: false,
+        index: false,
+        leaf_type: false,
+        #[cfg(feature = "std")]
+        platform: false,
+    };
+}
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Writes the frames one by one straight into the formatter, without building intermediate strings.
+///
+/// A `{pkg@ver}` header is written at the first frame and wherever the package changes.
+pub(crate) fn write_chain<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>> + Clone,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let leaf = |w: &mut dyn Write| {
+        if style.color {
+            w.write_str(RED)?;
+            leaf(w)?;
+            w.write_str(RESET)
+        } else {
+            leaf(w)
+        }
+    };
+    match style.layout {
+        Layout::Line => write_line(f, frames, style, leaf),
+        Layout::Tree => write_tree(f, frames, style, leaf),
+        Layout::Packages => write_packages(f, frames, style, leaf),
+        Layout::Reversed => write_reversed(f, frames, style, leaf),
+        Layout::Grouped => write_grouped(f, frames, style, leaf),
+    }
+}
+
+fn write_line<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>> + Clone,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let skipped = match style.tail {
+        usize::MAX => 0,
+        tail => frames.clone().count().saturating_sub(tail),
+    };
+    if skipped > 0 {
+        f.write_str("...")?;
+        f.write_str(style.separator)?;
+    }
+    let mut prev: Option<Frame<'a>> = None;
+    let mut frames = frames.enumerate().skip(skipped);
+    for (i, frame) in &mut frames {
+        if i == style.max_depth {
+            write!(f, "... (truncated, {} more)", 1 + frames.count())?;
+            f.write_str(style.separator)?;
+            break;
+        }
+        if style.index {
+            write!(f, "[{}] ", i)?;
+        }
+        write_prefix(f, &frame, style)?;
+        if !prev.is_some_and(|prev| prev.same_package(&frame)) {
+            write_header(f, &frame, style, i == 0)?;
+            f.write_char(' ')?;
+        }
+        write_location(f, &frame, style)?;
+        f.write_str(style.separator)?;
+        prev = Some(frame);
+    }
+    match style.leaf_indent {
+        "" => leaf(f),
+        indent => leaf(&mut Indented { f, indent }),
+    }
+}
+
+fn write_reversed<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>>,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let frames: Vec<Frame<'a>> = frames.collect();
+    leaf(f)?;
+    for (i, frame) in frames.iter().enumerate().rev() {
+        f.write_str(style.separator)?;
+        write_prefix(f, frame, style)?;
+        if i == 0 || !frames[i - 1].same_package(frame) {
+            write_header(f, frame, style, i == 0)?;
+            f.write_char(' ')?;
+        }
+        write_location(f, frame, style)?;
+    }
+    Ok(())
+}
+
+fn write_packages<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>>,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let mut prev: Option<Frame<'a>> = None;
+    for frame in frames {
+        match prev {
+            Some(prev) if prev.same_package(&frame) => f.write_str(style.separator)?,
+            Some(_) => f.write_char('\n')?,
+            None => {}
+        }
+        if !prev.is_some_and(|prev| prev.same_package(&frame)) {
+            write_header(f, &frame, style, prev.is_none())?;
+            f.write_char(' ')?;
+        }
+        write_prefix(f, &frame, style)?;
+        write_location(f, &frame, style)?;
+        prev = Some(frame);
+    }
+    if prev.is_some() {
+        f.write_str(style.separator)?;
+    }
+    leaf(f)
+}
+
+fn write_grouped<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>>,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    const INDENT: &str = "  ";
+
+    let mut prev: Option<Frame<'a>> = None;
+    for frame in frames {
+        if !prev.is_some_and(|prev| prev.same_package(&frame)) {
+            if prev.is_some() {
+                f.write_char('\n')?;
+            }
+            write_header(f, &frame, style, prev.is_none())?;
+        }
+        f.write_char('\n')?;
+        f.write_str(INDENT)?;
+        write_prefix(f, &frame, style)?;
+        write_location(f, &frame, style)?;
+        prev = Some(frame);
+    }
+    if prev.is_some() {
+        f.write_char('\n')?;
+        f.write_str(INDENT)?;
+    }
+    leaf(&mut Indented { f, indent: INDENT })
+}
+
+/// Each run of frames from one package is a branch of the tree, headed by its `{pkg@ver}`,
+/// with the frames of the run nested one level deeper each. The leaf hangs off the last frame.
+fn write_tree<'a>(
+    f: &mut Formatter<'_>,
+    frames: impl Iterator<Item = Frame<'a>> + Clone,
+    style: &Style<'_>,
+    leaf: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let mut prev: Option<Frame<'a>> = None;
+    let last_run = frames
+        .clone()
+        .enumerate()
+        .filter(|(_, frame)| !prev.replace(*frame).is_some_and(|p| p.same_package(frame)))
+        .last()
+        .map_or(0, |(i, _)| i);
+
+    let mut prev: Option<Frame<'a>> = None;
+    let mut nesting = 0;
+    let mut trunk = "   ";
+    for (i, frame) in frames.enumerate() {
+        if !prev.is_some_and(|prev| prev.same_package(&frame)) {
+            if i > 0 {
+                f.write_char('\n')?;
+            }
+            let branch = if i == last_run { "└─ " } else { "├─ " };
+            trunk = if i == last_run { "   " } else { "│  " };
+            nesting = 0;
+            f.write_str(branch)?;
+            write_prefix(f, &frame, style)?;
+            write_header(f, &frame, style, i == 0)?;
+            f.write_char(' ')?;
+        } else {
+            write_nesting(f, trunk, nesting)?;
+            write_prefix(f, &frame, style)?;
+        }
+        write_location(f, &frame, style)?;
+        nesting += 1;
+        prev = Some(frame);
+    }
+    if prev.is_none() {
+        return leaf(f);
+    }
+    write_nesting(f, trunk, nesting)?;
+    // The continuation lines of the leaf start under its first character
+    let indent = trunk.to_string() + &"   ".repeat(nesting);
+    leaf(&mut Indented { f, indent: &indent })
+}
+
+/// Starts a new tree line `nesting` levels below the head of the current branch
+fn write_nesting(f: &mut Formatter<'_>, trunk: &str, nesting: usize) -> Result {
+    f.write_char('\n')?;
+    f.write_str(trunk)?;
+    for _ in 1..nesting {
+        f.write_str("   ")?;
+    }
+    f.write_str("└─ ")
+}
+
+fn write_prefix(f: &mut Formatter<'_>, frame: &Frame<'_>, style: &Style<'_>) -> Result {
+    #[cfg(feature = "time")]
+    if style.time {
+        let since_epoch = frame
+            .timestamp
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        write!(
+            f,
+            "[{}.{:03}] ",
+            since_epoch.as_secs(),
+            since_epoch.subsec_millis()
+        )?;
+    }
+    if let (true, Some(thread)) = (style.thread, frame.thread) {
+        write!(f, "[{}] ", thread)?;
+    }
+    Ok(())
+}
+
+/// Writes `{pkg@ver}`, or `{pkg@ver build=...}` for the `top` header once `Report::set_build_info` was called
+pub(crate) fn write_header(
+    f: &mut Formatter<'_>,
+    frame: &Frame<'_>,
+    style: &Style<'_>,
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))] top: bool,
+) -> Result {
+    if style.color {
+        f.write_str(BOLD)?;
+    }
+    f.write_char('{')?;
+    match (style.escape_packages, style.no_version) {
+        (true, true) => write_escaped_package(f, frame.pkg_name)?,
+        (true, false) => {
+            write_escaped_package(f, frame.pkg_name)?;
+            f.write_char('@')?;
+            write_escaped_package(f, frame.pkg_version)?;
+        }
+        (false, true) => f.write_str(frame.pkg_name)?,
+        (false, false) => write!(f, "{}@{}", frame.pkg_name, frame.pkg_version)?,
+    }
+    #[cfg(feature = "std")]
+    if top {
+        if let Some(build) = Report::build_info() {
+            write!(f, " build={}", build)?;
+        }
+        if style.platform {
+            write!(
+                f,
+                " os={} arch={}",
+                Report::target_os(),
+                Report::target_arch()
+            )?;
+        }
+    }
+    f.write_char('}')?;
+    if style.color {
+        f.write_str(RESET)?;
+    }
+    Ok(())
+}
+
+/// Writes `s` with every character other than ASCII letters, digits, `-`, `_`, `.` and `+` as `%XX` per UTF-8 byte
+fn write_escaped_package(f: &mut Formatter<'_>, s: &str) -> Result {
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+') {
+            f.write_char(c)?;
+        } else {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                write!(f, "%{:02X}", byte)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_omitted(f: &mut Formatter<'_>, frame: &Frame<'_>, count: usize) -> Result {
+    let frames = if count == 1 { "frame" } else { "frames" };
+    write!(
+        f,
+        "... ({} {} in {}@{}) ...",
+        count, frames, frame.pkg_name, frame.pkg_version
+    )
+}
+
+pub(crate) fn write_location(
+    f: &mut Formatter<'_>,
+    frame: &Frame<'_>,
+    style: &Style<'_>,
+) -> Result {
+    if let Some(module) = frame.module {
+        write!(f, "{} ", module)?;
+    }
+    if style.color {
+        f.write_str(DIM)?;
+    }
+    if style.hash_paths {
+        write!(f, "{:08x}", fnv1a(frame.file))?;
+    } else {
+        if let Some(root) = frame.root {
+            write_path(f, root)?;
+            f.write_char('/')?;
+        }
+        write_path(f, frame.file)?;
+    }
+    write!(f, "{}", LineColumn(frame))?;
+    if style.color {
+        f.write_str(RESET)?;
+    }
+    for (i, (key, value)) in frame.meta.iter().enumerate() {
+        f.write_str(if i == 0 { " {" } else { " " })?;
+        write!(f, "{}={}", key, value)?;
+    }
+    if !frame.meta.is_empty() {
+        f.write_char('}')?;
+    }
+    if let Some(context) = frame.context {
+        write!(f, " ({})", context)?;
+    }
+    Ok(())
+}
+
+/// Writes `:line:column`, or just `:line` for hand-built frames whose column is unknown (`0`)
+pub(crate) struct LineColumn<'a, 'b>(pub(crate) &'a Frame<'b>);
+
+impl Display for LineColumn<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.0.column {
+            0 => write!(f, ":{}", self.0.line),
+            column => write!(f, ":{}:{}", self.0.line, column),
+        }
+    }
+}
+
+/// Writes through to `f`, starting every new line with `indent`
+struct Indented<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    indent: &'a str,
+}
+
+impl Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.f.write_char('\n')?;
+                self.f.write_str(self.indent)?;
+            }
+            self.f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a `caused by:` line for every error of the `source()` chain of `leaf`.
+///
+/// Unlike the frames of a `Report`, the chain comes from foreign types and may loop back on itself,
+/// so it stops with a `... (cycle)` line at the first error it already went through. An error is identified by
+/// its address and vtable, as a newtype shares its address with the error it wraps. In case the same error gets
+/// another vtable, the walk also stops after `MAX_CAUSES` lines.
+fn write_causes(f: &mut dyn Write, leaf: &(dyn Error + 'static)) -> Result {
+    const MAX_CAUSES: usize = 64;
+
+    let mut seen: Vec<*const dyn Error> = alloc::vec![leaf];
+    let mut cause = leaf.source();
+    while let Some(err) = cause {
+        if seen.iter().any(|&seen| ptr::eq(seen, err)) {
+            return f.write_str("\ncaused by: ... (cycle)");
+        }
+        if seen.len() > MAX_CAUSES {
+            return f.write_str("\ncaused by: ... (truncated)");
+        }
+        seen.push(err);
+        write!(f, "\ncaused by: {}", err)?;
+        cause = err.source();
+    }
+    Ok(())
+}
+
+/// Writes through to the inner writer with control characters escaped, like `\n` or `\u{1b}`
+struct Sanitized<'a>(&'a mut dyn Write);
+
+impl Write for Sanitized<'_> {
+    fn write_str(&mut self, s: &str) -> Result {
+        for c in s.chars() {
+            match c {
+                '\n' => self.0.write_str("\\n")?,
+                '\r' => self.0.write_str("\\r")?,
+                '\t' => self.0.write_str("\\t")?,
+                c if c.is_control() => write!(self.0, "\\u{{{:x}}}", c as u32)?,
+                c => self.0.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes `name` with every path shortened to its last segment, like `Box<dyn Error>` for `alloc::boxed::Box<dyn core::error::Error>`
+fn write_short_type(f: &mut dyn Write, name: &str) -> Result {
+    let mut start = 0;
+    for (i, c) in name.char_indices() {
+        if !(c.is_alphanumeric() || c == '_' || c == ':') {
+            f.write_str(name[start..i].rsplit("::").next().unwrap_or_default())?;
+            f.write_char(c)?;
+            start = i + c.len_utf8();
+        }
+    }
+    f.write_str(name[start..].rsplit("::").next().unwrap_or_default())
+}
+
+/// 32-bit FNV-1a of `path`, with `\` hashed as `/` so the same file hashes the same on every platform
+fn fnv1a(path: &str) -> u32 {
+    path.bytes().fold(0x811c_9dc5, |hash, byte| {
+        let byte = if byte == b'\\' { b'/' } else { byte };
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Writes `path` with Windows `\` separators turned into `/`, so frames read the same on every platform
+fn write_path(f: &mut Formatter<'_>, path: &str) -> Result {
+    for (i, part) in path.split('\\').enumerate() {
+        if i > 0 {
+            f.write_char('/')?;
+        }
+        f.write_str(part)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{display, Frame, Report};
+
+impl Report {
+    /// Renders the propagation path as a Graphviz DOT digraph, with no graphviz dependency.
+    ///
+    /// There is one box per frame, labeled `pkg@ver\nfile:line:col`, and an edge from every frame to the next one,
+    /// ending at an ellipse for the leaf error. Edges which cross into another package are dashed.
+    ///
+    /// ```
+    /// let report = erreport::Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let dot = report.to_dot();
+    /// assert!(dot.starts_with("digraph report {"));
+    /// assert_eq!(dot.matches("shape=box").count(), 2);
+    /// assert_eq!(dot.matches(" -> ").count(), 2);
+    /// assert_eq!(dot.matches("style=dashed").count(), 1);
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph report {\n");
+        let mut prev: Option<(usize, Frame<'_>)> = None;
+        for (i, frame) in self.frames().enumerate() {
+            let _ = write!(out, "    frame{} [shape=box, label=\"", i);
+            let _ = write!(Escape(&mut out), "{}@{}", frame.pkg_name, frame.pkg_version);
+            out.push_str("\\n");
+            for (j, part) in frame.file.split('\\').enumerate() {
+                if j > 0 {
+                    out.push('/');
+                }
+                let _ = Escape(&mut out).write_str(part);
+            }
+            let _ = writeln!(out, "{}\"];", display::LineColumn(&frame));
+            if let Some((prev_i, prev)) = prev {
+                let _ = write!(out, "    frame{} -> frame{}", prev_i, i);
+                if !prev.same_package(&frame) {
+                    out.push_str(" [style=dashed]");
+                }
+                out.push_str(";\n");
+            }
+            prev = Some((i, frame));
+        }
+        out.push_str("    leaf [shape=ellipse, label=\"");
+        let _ = write!(Escape(&mut out), "{}", display::Leaf(self));
+        out.push_str("\"];\n");
+        if let Some((prev_i, _)) = prev {
+            let _ = writeln!(out, "    frame{} -> leaf;", prev_i);
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Escapes everything written through it as the inside of a DOT string
+struct Escape<'a>(&'a mut String);
+
+impl Write for Escape<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.push_str("\\\""),
+                '\\' => self.0.push_str("\\\\"),
+                '\n' => self.0.push_str("\\n"),
+                '\r' => {}
+                c => self.0.push(c),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use alloc::boxed::Box;
+use core::error::Error;
+
+use crate::{__private, AggregateError, MsgError, NoneError, Report, StrError};
+
+/// Implements `From<$ty> for Report`, recording the location of the `?` but not the package
+macro_rules! impl_from {
+    ($($(#[$attr:meta])* $ty:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl From<$ty> for Report {
+                #[track_caller]
+                fn from(err: $ty) -> Self {
+                    __private::capture(
+                        "<unknown>",
+                        "<unknown>",
+                        __private::Paths::Absolute(None),
+                        core::panic::Location::caller(),
+                        Box::new(err),
+                        core::any::type_name::<$ty>(),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    core::fmt::Error,
+    core::num::ParseIntError,
+    core::num::ParseFloatError,
+    core::num::TryFromIntError,
+    core::str::ParseBoolError,
+    core::str::Utf8Error,
+    core::char::ParseCharError,
+    alloc::string::FromUtf8Error,
+    #[cfg(feature = "std")]
+    std::io::Error,
+    MsgError,
+    StrError,
+    NoneError,
+    AggregateError,
+);
+
+impl From<Box<dyn Error>> for Report {
+    #[track_caller]
+    fn from(err: Box<dyn Error>) -> Self {
+        __private::capture(
+            "<unknown>",
+            "<unknown>",
+            __private::Paths::Absolute(None),
+            core::panic::Location::caller(),
+            err,
+            core::any::type_name::<Box<dyn Error>>(),
+        )
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for Report {
+    #[track_caller]
+    fn from(err: Box<dyn Error + Send + Sync>) -> Self {
+        Report::from(err as Box<dyn Error>)
+    }
+}
@@ -0,0 +1,118 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::Report;
+
+impl Report {
+    /// Renders the chain as a compact JSON document, in the same shape as the `serde` feature produces:
+    /// `{"frames":[{"pkg_name":"..","pkg_version":"..","file":"..","root":null,"line":1,"column":1,"context":null,"code":null,"meta":[],"module":null,"thread":"main"}],"cause":".."}`
+    ///
+    /// It doesn't need the `serde` feature. Quotes, backslashes and control characters are escaped:
+    ///
+    /// ```
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "a \"quoted\"\nline\\\u{1}");
+    /// let json = report.to_json_string();
+    /// assert!(json.ends_with(r#","cause":"a \"quoted\"\nline\\\u0001"}"#));
+    /// # #[cfg(feature = "serde")] {
+    /// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(value["cause"], "a \"quoted\"\nline\\\u{1}");
+    /// assert_eq!(json, serde_json::to_string(&report).unwrap());
+    /// # }
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\"frames\":[");
+        for (i, frame) in self.frames().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"pkg_name\":");
+            push_str_value(&mut out, frame.pkg_name);
+            out.push_str(",\"pkg_version\":");
+            push_str_value(&mut out, frame.pkg_version);
+            out.push_str(",\"file\":");
+            push_str_value(&mut out, frame.file);
+            out.push_str(",\"root\":");
+            push_opt_str_value(&mut out, frame.root);
+            let _ = write!(out, ",\"line\":{},\"column\":{}", frame.line, frame.column);
+            out.push_str(",\"context\":");
+            push_opt_str_value(&mut out, frame.context);
+            out.push_str(",\"code\":");
+            push_opt_str_value(&mut out, frame.code);
+            out.push_str(",\"meta\":[");
+            for (i, (key, value)) in frame.meta.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push('[');
+                push_str_value(&mut out, key);
+                out.push(',');
+                push_str_value(&mut out, value);
+                out.push(']');
+            }
+            out.push(']');
+            out.push_str(",\"module\":");
+            push_opt_str_value(&mut out, frame.module);
+            #[cfg(feature = "time")]
+            {
+                let since_epoch = frame
+                    .timestamp
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default();
+                let _ = write!(
+                    out,
+                    ",\"timestamp\":{{\"secs_since_epoch\":{},\"nanos_since_epoch\":{}}}",
+                    since_epoch.as_secs(),
+                    since_epoch.subsec_nanos()
+                );
+            }
+            out.push_str(",\"thread\":");
+            match frame.thread {
+                Some(thread) => {
+                    out.push('"');
+                    let _ = write!(Escape(&mut out), "{}", thread);
+                    out.push('"');
+                }
+                None => out.push_str("null"),
+            }
+            out.push('}');
+        }
+        out.push_str("],\"cause\":\"");
+        let _ = write!(Escape(&mut out), "{}", self.root_cause());
+        out.push_str("\"}");
+        out
+    }
+}
+
+fn push_str_value(out: &mut String, s: &str) {
+    out.push('"');
+    let _ = Escape(out).write_str(s);
+    out.push('"');
+}
+
+fn push_opt_str_value(out: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => push_str_value(out, s),
+        None => out.push_str("null"),
+    }
+}
+
+/// Escapes everything written through it as the inside of a JSON string
+struct Escape<'a>(&'a mut String);
+
+impl Write for Escape<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.push_str("\\\""),
+                '\\' => self.0.push_str("\\\\"),
+                '\n' => self.0.push_str("\\n"),
+                '\r' => self.0.push_str("\\r"),
+                '\t' => self.0.push_str("\\t"),
+                c if (c as u32) < 0x20 => write!(self.0, "\\u{:04x}", c as u32)?,
+                c => self.0.push(c),
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! erreport
 //! ---
 //! A `Result` helper to catch all the `Err` propagation path
@@ -7,9 +8,9 @@
 //! When an `Err` propagated, we need a method catch all the propagation path.
 //! 
 //! So we can get this report in `release` mode with `debug=0`:
-//! ```
-//! {package0@version0} src/lib.rs:56 -> src/xx.rs:23 -> {package1@version1} src/lib.rs:42 -> src/yy.rs:632 -> src/zz.rs:56  
-//!   -> {package2@version2} src/lib.rs:251 -> InvalidXXX
+//! ```text
+//! {package0@version0} src/lib.rs:56:12 -> src/xx.rs:23:5 -> {package1@version1} src/lib.rs:42:9 -> src/yy.rs:632:17 -> src/zz.rs:56:20  
+//!   -> {package2@version2} src/lib.rs:251:13 -> InvalidXXX
 //! ```
 //! 
 //! #### Pros over `Backtrace`
@@ -24,118 +25,2169 @@
 //! erreport::prepare!(); 
 //! 
 //! fn test() -> Report<()> {
-//!     any_result_impl_std_error_Error.report()?;
-//!     any_result_impl_std_error_Error.report()?;
+//!     "1".parse::<u8>().report()?;
+//!     "x".parse::<u8>().report()?;
 //!     Ok(())
 //! }
 //! ```
 //! 
+//! ### The generated trait
+//! `.report()` and its variants are methods of the `ToReport` trait which `prepare!` generates in your crate, so they
+//! resolve at the call site like any trait method: the trait must be in scope there. That holds in the module which
+//! invoked `prepare!`, other modules import it, like `use crate::ToReport;`, or `use crate::errors::ToReport;` for a
+//! `prepare!` in `mod errors`. The macros which call these methods, `report!`, `report_with!`, `report_msg!`, `bail!`,
+//! `ensure!` and `aggregate!`, and the calls inserted by `#[auto_report]`, need it in scope just the same.
+//!
 //! ### How to access the actual Error?
 //! ```rust
+//! # use std::error::Error;
+//! # erreport::prepare!();
+//! # fn test() -> Report<()> { "x".parse::<u8>().report()?; Ok(()) }
 //! fn main() {
 //!     if let Err(err) = test() {
 //!         // This method will bypass all the `Report` wrappers and get the first actual `Error` value.
-//!         err.source() 
+//!         err.source();
 //!     }
 //! }
 //! ```
+//!
+//! `Report` is not `Send`, as it accepts any error. Where it has to cross threads, like out of a `tokio::spawn` task,
+//! use `report_sync` instead, which only accepts `Send + Sync` errors and gives a `SyncReport`.
+//!
+//! The `erreport::prelude` module re-exports the macros and types you usually need, see its documentation for the list.
+//!
+//! ### Features
+//! * `std` (default): disable it for `no_std` + `alloc` targets, the crate then relies on `core::error::Error` (Rust 1.81+).
+//! * `serde`: implements `serde::Serialize` for `Report`.
+//! * `time`: records a `SystemTime` timestamp on every frame, shown by `Report::display_with_time`.
+//! * `log`: adds `report_logged`, which also emits every conversion as a `log` record.
+//! * `tracing`: adds `report_traced`, which also emits every conversion as a `tracing` event.
+//! * `slog`: adds `Report::to_slog_kv`, the outermost frame and the leaf error as structured `slog` key-values.
+//!   It doesn't depend on the `log` or `tracing` features.
+//! * `sentry`: adds `Report::to_sentry_event`, which maps the frames to the stacktrace of a Sentry exception.
+//!   It only depends on `sentry-core`, whose `Event` is the one `sentry::capture_event` takes.
+//! * `axum`: implements `axum::response::IntoResponse` for `Report`, responding with `Report::http_status`
+//!   and only the leaf error, while the whole chain is logged.
+//! * `actix`: implements `actix_web::ResponseError` for `Report`, the same way.
+//! * `anyhow`: lets `anyhow::Result` be reported, the error is kept as an `AnyhowError` leaf.
+//! * `eyre`: lets `eyre::Result` be reported with an `EyreError` leaf, and adds `Report::into_eyre`.
+//! * `miette`: implements `miette::Diagnostic` for `Report`, with its `code()` and a help line.
+//! * `macros`: adds the `#[erreport::auto_report]` attribute, which inserts `.report()` before every `?` of a function.
+//! * `color`: adds `Report::display_colored`, which renders with ANSI colors on a terminal, following `NO_COLOR` and `CLICOLOR_FORCE`.
+//! * `from-conversions`: implements `From` for `Report` from the common `std` errors, like `io::Error` and
+//!   `ParseIntError`, so a plain `?` converts them. **The frame doesn't know its package**: it renders as
+//!   `{<unknown>@<unknown>} src/main.rs:2:13`, with the location of the `?` but not your crate's name and version,
+//!   and the path isn't made relative. Prefer `.report()`, this is a trade-off for terser code.
+//!   A blanket `From<E: Error>` is not possible, as it would overlap with `From<Report> for Report`.
+//!
+//!   ```
+//!   # #[cfg(feature = "from-conversions")] {
+//!   fn port(text: &str) -> erreport::Result<u16> {
+//!       let port = text.parse::<u16>()?;
+//!       Ok(port)
+//!   }
+//!
+//!   let report = port("x").unwrap_err();
+//!   assert!(report.to_string().starts_with("{<unknown>@<unknown>} "));
+//!   assert!(report.to_string().ends_with(" -> invalid digit found in string"));
+//!   // The frame points at the `?`, not into erreport
+//!   assert!(!report.file.ends_with("from.rs"));
+//!   assert!(report.leaf_is::<std::num::ParseIntError>());
+//!   # }
+//!   ```
+//!
+//! * `capture-leaf`: renders the leaf error into `Report::leaf_display` and `Report::leaf_debug` when it is first reported,
+//!   and `Display`, `Debug` and `snapshot` use those strings instead of formatting the live error again.
+//!   It costs two `format!` calls per conversion.
+//! * `env-verbosity`: lets the `ERREPORT_VERBOSITY` environment variable choose what the default `Display` shows,
+//!   see `Verbosity`. Without it, `Display` never looks at the environment.
+
+extern crate alloc;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::error::Error;
+
+mod aggregate;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+mod compat;
+#[cfg(feature = "miette")]
+mod diagnostic;
+mod display;
+mod dot;
+#[cfg(feature = "from-conversions")]
+mod from;
+mod json;
+mod markdown;
+#[cfg(feature = "std")]
+mod panic;
+pub mod prelude;
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "sentry")]
+mod sentry_event;
+#[cfg(feature = "slog")]
+mod slog_kv;
+mod snapshot;
+mod sync;
+#[cfg(any(feature = "axum", feature = "actix"))]
+mod web;
 
+pub use aggregate::AggregateError;
+#[cfg(feature = "anyhow")]
+pub use compat::AnyhowError;
+#[cfg(feature = "eyre")]
+pub use compat::EyreError;
+#[cfg(feature = "env-verbosity")]
+pub use display::Verbosity;
+pub use display::{FilteredDisplay, ReportDisplay, WrappedDisplay};
+/// Rewrites every `expr?` in a function body to `expr.report()?`, so each `?` records a frame without spelling it out.
+///
+/// ```
+/// erreport::prepare!();
+///
+/// #[erreport::auto_report]
+/// fn parse(a: &str, b: &str) -> Report<u16> {
+///     let a = a.parse::<u8>()?;
+///     // Already reported, so it isn't wrapped twice
+///     let b = b.parse::<u8>().report_with("b")?;
+///     Ok(u16::from(a) + u16::from(b))
+/// }
+///
+/// assert_eq!(parse("1", "2").unwrap(), 3);
+/// assert_eq!(parse("x", "2").unwrap_err().depth(), 1);
+/// assert!(parse("1", "x").unwrap_err().to_string().contains("(b)"));
+///
+/// #[erreport::auto_report]
+/// fn port(text: &str) -> Report<u8> {
+///     Ok(erreport::report_with!(text.parse::<u8>(), "port {}", text)?)
+/// }
+///
+/// assert_eq!(port("x").unwrap_err().depth(), 1);
+/// ```
+///
+/// A `?` is left alone when its operand already ends in a `report*` or `ok_or_report*` method, or is a `report!`
+/// or `report_with!` call, however the macro path is spelled.
+/// Closures, `async` blocks and nested items are not rewritten either, as a `?` there doesn't return from the function.
+///
+/// Caveats:
+/// * The inserted `.report()` needs [the generated trait](crate#the-generated-trait) in scope at the call site.
+/// * Every other `?` operand must be a `Result` which can be reported; an `Option` needs an explicit `.ok_or_report()`.
+/// * A `?` inside another macro call, like `vec![a?]`, is only a token stream to the attribute and is not rewritten.
+/// * The name differs from `report!`, because attribute and function-like macros share one namespace.
+#[cfg(feature = "macros")]
+pub use erreport_macros::auto_report;
+#[cfg(feature = "std")]
+pub use panic::install_panic_hook;
+#[cfg(feature = "std")]
+pub use scope::{context_scope, pop_context, push_context, scope, ContextGuard};
+#[cfg(feature = "slog")]
+pub use slog_kv::SlogKv;
+pub use snapshot::{OwnedFrame, ReportSnapshot};
+pub use sync::SyncReport;
 
-use std::error::Error;
+/// `Result<T, Report>`, like `fn f() -> erreport::Result<T>`. Other error types can still be given as `E`
+pub type Result<T, E = Report> = core::result::Result<T, E>;
 
 /// You can use `.source()` to get the first real source in Report
+///
+/// The fields are public, but to build one by hand use `Report::new`. Data most frames don't carry lives in the
+/// `extra` field, a `ReportExtra` which is only allocated when one of its fields is set, so a `Result<T, Report>`
+/// stays small. The `context`, `code`, `meta`, `module` and `message` fields used to be fields of `Report` itself;
+/// read them with `report.extra().context` and set them with `report.extra_mut().context = ..` instead:
+///
+/// ```
+/// use erreport::{Report, ReportExtra};
+///
+/// assert!(std::mem::size_of::<Report>() < 128);
+///
+/// let report = Report {
+///     pkg_name: "app",
+///     pkg_version: "0.1.0",
+///     file: "src/main.rs",
+///     line: 10,
+///     column: 5,
+///     location: None,
+///     # #[cfg(feature = "time")]
+///     # timestamp: std::time::SystemTime::now(),
+///     # #[cfg(feature = "std")]
+///     # thread: None,
+///     extra: Some(Box::new(ReportExtra {
+///         context: Some("loading".to_string()),
+///         ..ReportExtra::default()
+///     })),
+///     err: "boom".into(),
+/// };
+/// assert_eq!(report.extra().context.as_deref(), Some("loading"));
+/// assert_eq!(report.to_string(), "{app@0.1.0} src/main.rs:10:5 (loading) -> boom");
+/// ```
+///
+/// Each `Report` owns the error it wraps, so a chain can't loop back on itself, and it is always walked with a loop
+/// rather than recursion: rendering, `source()`, `frames()` and dropping can't overflow the stack however deep
+/// the chain is. The `source()` chain of the leaf error is only followed by `Report::display_full_causes`.
+///
+/// ```
+/// use std::error::Error;
+///
+/// let mut report = erreport::Report::new("app", "0.1.0", "src/main.rs", 0, "boom");
+/// for line in 1..=200_000 {
+///     report = erreport::Report::new("app", "0.1.0", "src/main.rs", line, report);
+/// }
+///
+/// assert_eq!(report.frames().count(), 200_001);
+/// assert!(report.to_string().starts_with("{app@0.1.0} src/main.rs:200000 -> src/main.rs:199999 -> "));
+/// assert!(report.to_string().ends_with(" -> src/main.rs:0 -> boom"));
+/// assert_eq!(report.source().unwrap().to_string(), "boom");
+/// drop(report);
+/// ```
 pub struct Report {
     pub pkg_name: &'static str,
     pub pkg_version: &'static str,
     pub file: &'static str,
     pub line: u32,
+    /// The 1-based column of the call site, as reported by `Location::column()`.
+    ///
+    /// If you construct a `Report` by hand and don't know the column, use `0`: the frame then renders as `file:line`.
+    pub column: u32,
+    /// The call site as the compiler recorded it, see `Report::location`
+    pub location: Option<&'static core::panic::Location<'static>>,
+    /// When this frame was created
+    #[cfg(feature = "time")]
+    pub timestamp: std::time::SystemTime,
+    /// The thread which created this frame. Keeping the handle is a reference count increment,
+    /// its name, or its `ThreadId` if it has none, is only looked up when rendering
+    #[cfg(feature = "std")]
+    pub thread: Option<std::thread::Thread>,
+    /// The rarely set data of this frame, `None` until one of its fields is set. See `Report::extra`
+    pub extra: Option<Box<ReportExtra>>,
     pub err: Box<dyn Error>,
 }
 
-impl std::fmt::Debug for Report {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string(true, 0))
+/// The data of a frame which most reports don't carry, kept in `Report::extra` and allocated by `Report::extra_mut` on first use
+#[derive(Debug, Default)]
+pub struct ReportExtra {
+    /// An optional message describing what was being done at this point, set by `report_with`
+    pub context: Option<String>,
+    /// A stable error code for this point, set by `report_coded`
+    pub code: Option<&'static str>,
+    /// Structured key-value data about this point, like `user_id=42`, set by `report_meta` and `context_scope`
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let report = "x".parse::<u8>().report_meta(&[("user_id", 42), ("retry", 3)]).unwrap_err();
+    /// assert_eq!(report.extra().meta, [("user_id", "42".to_string()), ("retry", "3".to_string())]);
+    /// assert!(report.to_string().ends_with(&format!(":{} {{user_id=42 retry=3}} -> invalid digit found in string", report.column)));
+    /// ```
+    pub meta: Vec<(&'static str, String)>,
+    /// The `module_path!()` of the call site, only recorded by the `report!` macro
+    pub module: Option<&'static str>,
+    /// The directory a relative `file` is under, only set by `prepare!(absolute_paths)` when the compiler recorded
+    /// a relative path. The frame then renders as `{root}/{file}`
+    pub root: Option<&'static str>,
+    /// A message rendered instead of the leaf error by `Display`, set by `report_as`. See `Report::message`
+    pub message: Option<&'static str>,
+    /// The `type_name` of the leaf error, like `core::num::error::ParseIntError`, only set on the innermost frame.
+    /// See `Report::leaf_type`
+    pub leaf_type: Option<&'static str>,
+    /// The `Display` of the leaf error, taken when this frame was reported. Only set on the innermost frame
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let report = "x".parse::<u8>().report().unwrap_err();
+    /// assert_eq!(report.extra().leaf_display.as_deref(), Some("invalid digit found in string"));
+    /// assert_eq!(report.extra().leaf_debug.as_deref(), Some("ParseIntError { kind: InvalidDigit }"));
+    /// ```
+    #[cfg(feature = "capture-leaf")]
+    pub leaf_display: Option<String>,
+    /// The `Debug` of the leaf error, taken when this frame was reported. Only set on the innermost frame
+    #[cfg(feature = "capture-leaf")]
+    pub leaf_debug: Option<String>,
+}
+
+/// What `Report::extra` gives for a frame without any
+static NO_EXTRA: ReportExtra = ReportExtra {
+    context: None,
+    code: None,
+    meta: Vec::new(),
+    module: None,
+    root: None,
+    message: None,
+    leaf_type: None,
+    #[cfg(feature = "capture-leaf")]
+    leaf_display: None,
+    #[cfg(feature = "capture-leaf")]
+    leaf_debug: None,
+};
+
+/// A borrowed view of a single `Report` frame, yielded by `Report::frames`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Frame<'a> {
+    pub pkg_name: &'a str,
+    pub pkg_version: &'a str,
+    pub file: &'a str,
+    /// See `ReportExtra::root`
+    pub root: Option<&'a str>,
+    pub line: u32,
+    pub column: u32,
+    pub context: Option<&'a str>,
+    pub code: Option<&'a str>,
+    pub meta: &'a [(&'static str, String)],
+    pub module: Option<&'a str>,
+    #[cfg(feature = "time")]
+    pub timestamp: std::time::SystemTime,
+    pub thread: Option<FrameThread<'a>>,
+}
+
+/// The thread a frame was created on, see `Frame::thread`.
+///
+/// It renders as the name of the thread, or as its `ThreadId`, like `ThreadId(3)`, if it has none.
+#[derive(Debug, Clone, Copy)]
+pub enum FrameThread<'a> {
+    /// The handle kept by a `Report`
+    #[cfg(feature = "std")]
+    Handle(&'a std::thread::Thread),
+    /// An already rendered name, like the one kept by an `OwnedFrame`
+    Name(&'a str),
+}
+
+impl core::fmt::Display for FrameThread<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            FrameThread::Handle(thread) => match thread.name() {
+                Some(name) => f.write_str(name),
+                None => write!(f, "{:?}", thread.id()),
+            },
+            FrameThread::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Two handles are the same thread when their `ThreadId`s are, otherwise the rendered names are compared
+impl PartialEq for FrameThread<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        use alloc::string::ToString;
+
+        match (self, other) {
+            #[cfg(feature = "std")]
+            (FrameThread::Handle(a), FrameThread::Handle(b)) => a.id() == b.id(),
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+impl Eq for FrameThread<'_> {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FrameThread<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An item of `Report::iter`: a frame, or the leaf error which ends the chain
+#[derive(Debug, Clone, Copy)]
+pub enum Node<'a> {
+    Frame(Frame<'a>),
+    Leaf(&'a (dyn Error + 'static)),
+}
+
+impl Frame<'_> {
+    fn same_package(&self, other: &Frame<'_>) -> bool {
+        self.pkg_name == other.pkg_name && self.pkg_version == other.pkg_version
+    }
+}
+
+impl core::fmt::Debug for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        display::write_chain(f, self.frames(), &display::Style::DEFAULT, |f| {
+            write!(f, "{:?}", display::LeafDebug(self))
+        })
+    }
+}
+/// A `{pkg@ver}` header starts every run of frames from one package, however the reports were nested.
+/// Reporting a `Result<T, Report>` again just adds a frame on top, in the same package or in another one:
+///
+/// ```
+/// # erreport::prepare!();
+/// use erreport::Report as R;
+///
+/// let report = R::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+/// let report = R::new("dep", "1.2.0", "src/api.rs", 3, report);
+/// let report = R::new("dep", "2.0.0", "src/lib.rs", 9, report);
+/// let report = R::new("app", "0.1.0", "src/main.rs", 10, report);
+/// let report = R::new("app", "0.1.0", "src/main.rs", 12, report);
+/// let report = R::new("dep", "1.2.0", "src/lib.rs", 1, report);
+/// assert_eq!(
+///     report.to_string(),
+///     "{dep@1.2.0} src/lib.rs:1 -> {app@0.1.0} src/main.rs:12 -> src/main.rs:10 \
+///      -> {dep@2.0.0} src/lib.rs:9 -> {dep@1.2.0} src/api.rs:3 -> src/lib.rs:7 -> boom"
+/// );
+///
+/// let rereported = Err::<(), _>(report).report().unwrap_err();
+/// assert!(rereported.to_string().starts_with("{erreport@0.3.0} "));
+/// assert!(rereported.to_string().contains(" -> {dep@1.2.0} src/lib.rs:1 -> {app@0.1.0} "));
+/// assert_eq!(rereported.depth(), 7);
+/// ```
+///
+/// The frames are written one after the other straight into the formatter. The output is byte for byte the one
+/// of building a `String` per frame and nesting them:
+///
+/// ```
+/// use erreport::Report as R;
+///
+/// fn nested(report: &R, outer: Option<&R>) -> String {
+///     let header = match outer {
+///         Some(outer) if (outer.pkg_name, outer.pkg_version) == (report.pkg_name, report.pkg_version) => String::new(),
+///         _ => format!("{{{}@{}}} ", report.pkg_name, report.pkg_version),
+///     };
+///     let rest = match report.err.downcast_ref::<R>() {
+///         Some(inner) => nested(inner, Some(report)),
+///         None => report.err.to_string(),
+///     };
+///     format!("{}{}:{} -> {}", header, report.file, report.line, rest)
+/// }
+///
+/// let mut report = R::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+/// for (pkg, line) in [("dep", 3), ("dep", 5), ("app", 10), ("app", 12), ("dep", 1), ("app", 20)] {
+///     report = R::new(pkg, if pkg == "app" { "0.1.0" } else { "1.2.0" }, "src/lib.rs", line, report);
+/// }
+///
+/// assert_eq!(
+///     report.to_string(),
+///     "{app@0.1.0} src/lib.rs:20 -> {dep@1.2.0} src/lib.rs:1 -> {app@0.1.0} src/lib.rs:12 -> src/lib.rs:10 \
+///      -> {dep@1.2.0} src/lib.rs:5 -> src/lib.rs:3 -> src/lib.rs:7 -> boom"
+/// );
+/// assert_eq!(report.to_string(), nested(&report, None));
+/// ```
+impl core::fmt::Display for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "env-verbosity")]
+        match Report::verbosity() {
+            Verbosity::Full => {}
+            Verbosity::PathOnly => return write!(f, "{}", display::PathOnly(self)),
+            Verbosity::LeafOnly => return write!(f, "{}", display::Leaf(self)),
+        }
+        display::write_chain(f, self.frames(), &display::Style::DEFAULT, |f| {
+            write!(f, "{}", display::Leaf(self))
+        })
+    }
+}
+/// Drops the chain with a loop, as the drop glue of the nested boxes would recurse once per frame.
+///
+/// Because of it, the fields of a `Report` can't be moved out by destructuring, which fails with E0509:
+///
+/// ```compile_fail,E0509
+/// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+/// let erreport::Report { err, .. } = report;
+/// ```
+///
+/// Use `Report::into_err` or `Report::into_leaf` instead, the other fields are `Copy` or can be cloned.
+impl Drop for Report {
+    fn drop(&mut self) {
+        drop(unwind(self.take_err()));
+    }
+}
+
+/// Takes every `Report` and `SyncReport` frame off `err`, one at a time, and gives back the leaf error
+fn unwind(mut err: Box<dyn Error>) -> Box<dyn Error> {
+    loop {
+        err = match err.downcast::<Report>() {
+            Ok(mut inner) => inner.take_err(),
+            Err(err) => match err.downcast::<SyncReport>() {
+                Ok(mut inner) => inner.0.take_err(),
+                Err(leaf) => return leaf,
+            },
+        };
     }
 }
-impl std::fmt::Display for Report {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string(false, 0))
+
+/// Stands in for the `err` taken out of a `Report`, boxing it doesn't allocate
+#[derive(Debug)]
+struct Taken;
+
+impl core::fmt::Display for Taken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("<taken>")
     }
 }
+impl Error for Taken {}
+
 impl Error for Report {
     /// This method will ignore the report stack and get the first real source
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        match self.err.downcast_ref::<Report>() {
-            Some(e) => e.source(),
-            None => Some(self.err.as_ref()),
+        Some(self.root_cause())
+    }
+}
+
+/// Two reports are equal when every frame has the same `pkg_name`, `pkg_version`, `file` and `line`,
+/// and their leaf errors render the same `Display` string.
+///
+/// The leaf errors are compared by their message only, not by their type. Columns and contexts are left out:
+///
+/// ```
+/// use erreport::Report;
+///
+/// let a = Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+/// let mut b = Report::new("app", "0.1.0", "src/main.rs", 10, std::io::Error::other("boom"));
+/// b.column = 5;
+/// b.extra_mut().context = Some("loading".to_string());
+/// assert_eq!(a, b);
+/// assert_ne!(a, Report::new("app", "0.1.0", "src/main.rs", 11, "boom"));
+/// ```
+impl PartialEq for Report {
+    fn eq(&self, other: &Self) -> bool {
+        use alloc::string::ToString;
+
+        self.depth() == other.depth()
+            && self.frames().zip(other.frames()).all(|(a, b)| {
+                a.pkg_name == b.pkg_name
+                    && a.pkg_version == b.pkg_version
+                    && a.file == b.file
+                    && a.line == b.line
+            })
+            && self.root_cause().to_string() == other.root_cause().to_string()
+    }
+}
+
+/// Serializes as `{"frames": [...], "cause": "..."}`, where `cause` is the `Display` of the leaf error
+///
+/// ```
+/// # erreport::prepare!();
+/// let result = Err::<(), _>(std::io::Error::other("no such file"));
+/// let report = result.report().report_with("loading the config").report().unwrap_err();
+///
+/// let json = serde_json::to_string(&report).unwrap();
+/// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// let frames = value["frames"].as_array().unwrap();
+/// assert_eq!(frames.len(), 3);
+/// assert_eq!(frames[1]["context"], "loading the config");
+/// assert_eq!(value["cause"], "no such file");
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Report {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        struct Frames<'a>(&'a Report);
+        impl serde::Serialize for Frames<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.frames())
+            }
         }
+
+        let mut state = serializer.serialize_struct("Report", 2)?;
+        state.serialize_field("frames", &Frames(self))?;
+        state.serialize_field(
+            "cause",
+            &alloc::string::ToString::to_string(self.root_cause()),
+        )?;
+        state.end()
     }
 }
 
 impl Report {
-    fn to_string(&self, is_debug: bool, index: u16) -> String {
-        let err_str = self
-            .err
-            .downcast_ref::<Self>()
-            .and_then(|report| {
-                if report.pkg_name == self.pkg_name && report.pkg_version == self.pkg_version {
-                    Some(report.to_string(is_debug, index + 1))
-                } else {
-                    None
-                }
+    /// Builds a `Report` frame by hand, e.g. in tests or when bridging from another error library.
+    ///
+    /// The column is `0`, `location` is `None`, `extra()` is empty, and the timestamp and thread are those of the caller.
+    pub fn new(
+        pkg_name: &'static str,
+        pkg_version: &'static str,
+        file: &'static str,
+        line: u32,
+        err: impl Into<Box<dyn Error>>,
+    ) -> Self {
+        Report {
+            pkg_name,
+            pkg_version,
+            file,
+            line,
+            column: 0,
+            location: None,
+            #[cfg(feature = "time")]
+            timestamp: std::time::SystemTime::now(),
+            #[cfg(feature = "std")]
+            thread: Some(std::thread::current()),
+            extra: None,
+            err: err.into(),
+        }
+    }
+
+    /// The context, code, meta and other data of this frame, all empty if it carries none
+    pub fn extra(&self) -> &ReportExtra {
+        self.extra.as_deref().unwrap_or(&NO_EXTRA)
+    }
+
+    /// Mutable access to the `extra()` data of this frame, allocated on first use.
+    ///
+    /// ```
+    /// let mut report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, "boom");
+    /// report.extra_mut().context = Some("parsing the port".into());
+    /// assert_eq!(report.to_string(), "{app@0.1.0} src/main.rs:10 (parsing the port) -> boom");
+    /// ```
+    pub fn extra_mut(&mut self) -> &mut ReportExtra {
+        self.extra.get_or_insert_with(Default::default)
+    }
+
+    /// Iterates the frames from this `Report` down to the innermost one, stopping before the first non-`Report` error
+    pub fn frames(&self) -> impl Iterator<Item = Frame<'_>> + Clone {
+        self.chain().map(Report::frame)
+    }
+
+    /// Iterates the frames like `frames()`, outermost first, then yields the leaf error last and stops.
+    ///
+    /// It lets a custom formatter handle the hops and the final cause in one loop:
+    ///
+    /// ```
+    /// use erreport::{Node, Report};
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let mut out = String::new();
+    /// for node in report.iter() {
+    ///     match node {
+    ///         Node::Frame(frame) => out.push_str(&format!("{}:{} | ", frame.file, frame.line)),
+    ///         Node::Leaf(err) => out.push_str(&format!("[{}]", err)),
+    ///     }
+    /// }
+    /// assert_eq!(out, "src/main.rs:10 | src/lib.rs:7 | [boom]");
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Node<'_>> + Clone {
+        self.frames()
+            .map(Node::Frame)
+            .chain(core::iter::once(Node::Leaf(self.root_cause())))
+    }
+
+    /// The outermost frame, the one of this `Report`: where the error was last reported
+    pub fn entry_frame(&self) -> Frame<'_> {
+        self.frame()
+    }
+
+    /// The deepest frame before the leaf error: where the error was first reported
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// assert_eq!(report.innermost_frame().file, "src/lib.rs");
+    /// assert_eq!(report.innermost_frame().line, report.entry_frame().line);
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/db.rs", 42, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let frame = report.innermost_frame();
+    /// assert_eq!((frame.pkg_name, frame.file, frame.line), ("dep", "src/lib.rs", 7));
+    /// assert_eq!(report.entry_frame().file, "src/main.rs");
+    /// ```
+    pub fn innermost_frame(&self) -> Frame<'_> {
+        self.chain().last().unwrap_or(self).frame()
+    }
+
+    /// The number of `Report` frames in the chain, not counting the leaf error
+    ///
+    /// ```
+    /// use erreport::Report as R;
+    ///
+    /// let report = R::new("app", "0.1.0", "src/lib.rs", 7, "boom");
+    /// assert_eq!(report.depth(), 1);
+    /// let report = R::new("app", "0.1.0", "src/main.rs", 3, R::new("app", "0.1.0", "src/lib.rs", 9, report));
+    /// assert_eq!(report.depth(), 3);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.chain().count()
+    }
+
+    /// The deepest non-`Report` error of the chain. Same as `source()`, but never `None`
+    pub fn root_cause(&self) -> &(dyn Error + 'static) {
+        self.innermost().err.as_ref()
+    }
+
+    /// Resolves the leaf source (like `source()`) and tries to downcast it to `E`
+    pub fn downcast_source_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.root_cause().downcast_ref::<E>()
+    }
+
+    /// Whether the leaf error is an `E`. The leaf keeps its concrete type however many times it was reported:
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let result = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::NotFound));
+    /// let report = result.report().report().report().unwrap_err();
+    /// assert_eq!(report.depth(), 3);
+    /// assert!(report.leaf_is::<std::io::Error>());
+    /// assert!(report.downcast_source_ref::<std::io::Error>().is_some());
+    /// ```
+    ///
+    /// A boxed `dyn Error` is stored as is, so it is downcast to the type inside the box. An `anyhow::Error`
+    /// can only be reached as an `AnyhowError`, its own `downcast_ref` needs `Send + Sync` which `E` doesn't promise.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// use std::error::Error;
+    ///
+    /// let result: Result<(), Box<dyn Error>> = Err(Box::new(std::io::Error::other("io")));
+    /// let report = result.report().unwrap_err();
+    /// assert!(report.leaf_is::<std::io::Error>());
+    /// assert_eq!(report.source().unwrap().to_string(), "io");
+    /// ```
+    pub fn leaf_is<E: Error + 'static>(&self) -> bool {
+        self.root_cause().is::<E>()
+    }
+
+    /// Consuming version of `downcast_source_ref`, gives the report back if the leaf is not an `E`
+    pub fn downcast_source<E: Error + 'static>(self) -> Result<Box<E>, Self> {
+        if self.downcast_source_ref::<E>().is_none() {
+            return Err(self);
+        }
+
+        match self.into_leaf().downcast::<E>() {
+            Ok(leaf) => Ok(leaf),
+            Err(_) => unreachable!("the leaf was checked to be an `E`"),
+        }
+    }
+
+    /// Moves the wrapped error out, which is the next frame unless this is the innermost one.
+    /// It stands in for `let Report { err, .. } = report;`, which `Drop` rules out:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, Report::new("app", "0.1.0", "src/lib.rs", 7, "boom"));
+    /// let inner = report.into_err().downcast::<Report>().unwrap();
+    /// assert_eq!(inner.line, 7);
+    /// assert_eq!(inner.into_err().to_string(), "boom");
+    /// ```
+    pub fn into_err(mut self) -> Box<dyn Error> {
+        self.take_err()
+    }
+
+    /// Moves the leaf error out, dropping every frame of the chain
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, std::io::Error::other("boom"));
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 12, report);
+    /// let leaf = report.into_leaf();
+    /// assert!(leaf.is::<std::io::Error>());
+    /// assert_eq!(leaf.to_string(), "boom");
+    /// ```
+    pub fn into_leaf(mut self) -> Box<dyn Error> {
+        unwind(self.take_err())
+    }
+
+    /// The `(pkg_name, pkg_version)` pairs the chain went through, outermost first, each listed once where it was first seen
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("rustls", "0.23.0", "src/conn.rs", 7, "bad certificate");
+    /// let report = Report::new("hyper", "1.4.0", "src/client.rs", 20, report);
+    /// let report = Report::new("app", "0.1.0", "src/net.rs", 3, report);
+    /// let report = Report::new("hyper", "1.4.0", "src/conn.rs", 9, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 12, report);
+    /// assert_eq!(
+    ///     report.packages(),
+    ///     [("app", "0.1.0"), ("hyper", "1.4.0"), ("rustls", "0.23.0")]
+    /// );
+    /// ```
+    pub fn packages(&self) -> Vec<(&str, &str)> {
+        let mut packages: Vec<(&str, &str)> = Vec::new();
+        for frame in self.frames() {
+            let package = (frame.pkg_name, frame.pkg_version);
+            if !packages.contains(&package) {
+                packages.push(package);
+            }
+        }
+        packages
+    }
+
+    /// How many frames each `(pkg_name, pkg_version)` contributed, in the order `packages()` gives.
+    ///
+    /// Only frames are counted, the leaf error is not one:
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("hyper", "1.4.0", "src/client.rs", 20, "connection reset");
+    /// let report = Report::new("app", "0.1.0", "src/net.rs", 3, report);
+    /// let report = Report::new("hyper", "1.4.0", "src/conn.rs", 9, report);
+    /// let report = Report::new("hyper", "1.4.0", "src/body.rs", 4, report);
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.frames_per_package(),
+    ///     [(("app", "0.1.0"), 2), (("hyper", "1.4.0"), 3)]
+    /// );
+    /// ```
+    pub fn frames_per_package(&self) -> Vec<((&str, &str), usize)> {
+        let mut counts: Vec<((&str, &str), usize)> = Vec::new();
+        for frame in self.frames() {
+            let package = (frame.pkg_name, frame.pkg_version);
+            match counts.iter_mut().find(|(seen, _)| *seen == package) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((package, 1)),
+            }
+        }
+        counts
+    }
+
+    /// The packages of `packages()` as one line for a log footer, sorted by name then version: `crates: app@0.1.0, hyper@1.4.0`.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("rustls", "0.23.0", "src/conn.rs", 7, "bad certificate");
+    /// let report = Report::new("hyper", "1.4.0", "src/client.rs", 20, report);
+    /// let report = Report::new("zed", "0.1.0", "src/net.rs", 3, report);
+    /// let report = Report::new("hyper", "1.4.0", "src/conn.rs", 9, report);
+    /// assert_eq!(report.package_summary(), "crates: hyper@1.4.0, rustls@0.23.0, zed@0.1.0");
+    /// ```
+    ///
+    /// The order doesn't depend on the path, so the same set of packages always gives the same line.
+    pub fn package_summary(&self) -> String {
+        let mut packages = self.packages();
+        packages.sort_unstable();
+        let mut summary = String::from("crates:");
+        for (i, (name, version)) in packages.into_iter().enumerate() {
+            summary.push_str(if i == 0 { " " } else { ", " });
+            summary.push_str(name);
+            summary.push('@');
+            summary.push_str(version);
+        }
+        summary
+    }
+
+    /// A hash of the propagation path, to group reports which went through the same places.
+    ///
+    /// It is the 64-bit FNV-1a of every frame's `pkg_name`, `pkg_version` and `file` bytes, each followed by a `0xff`
+    /// byte, then its `line` as 4 little-endian bytes, outermost frame first. The column, context and leaf error are left out,
+    /// so the same path fingerprints the same whatever the message says. It only depends on those values,
+    /// so it is stable across runs and platforms for the same build.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let path = |leaf: &'static str, line| {
+    ///     Report::new("app", "0.1.0", "src/main.rs", 10, Report::new("dep", "1.2.0", "src/lib.rs", line, leaf))
+    /// };
+    /// assert_eq!(path("timed out", 7).fingerprint(), path("connection refused", 7).fingerprint());
+    /// assert_ne!(path("timed out", 7).fingerprint(), path("timed out", 8).fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        fn hash(state: u64, bytes: &[u8]) -> u64 {
+            bytes.iter().fold(state, |state, byte| {
+                (state ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
             })
-            .unwrap_or(if is_debug {
-                format!("{:?}", self.err)
-            } else {
-                self.err.to_string()
-            });
+        }
+
+        self.frames().fold(0xcbf2_9ce4_8422_2325, |state, frame| {
+            let state = [frame.pkg_name, frame.pkg_version, frame.file]
+                .iter()
+                .fold(state, |state, field| {
+                    hash(hash(state, field.as_bytes()), &[0xff])
+                });
+            hash(state, &frame.line.to_le_bytes())
+        })
+    }
+
+    /// The error code of the outermost frame which carries one, see `report_coded`
+    pub fn code(&self) -> Option<&str> {
+        self.chain().find_map(|report| report.extra().code)
+    }
+
+    /// A process exit code for a CLI: the `code()` if it is a non-zero integer, like `"2"`, otherwise `1`.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let report = "x".parse::<u16>().report_coded("2").report().unwrap_err();
+    /// assert_eq!(report.exit_code(), 2);
+    /// ```
+    ///
+    /// The `Err` branch of `main` can then end with `report.eprint(); std::process::exit(report.exit_code());`.
+    /// A code which isn't a number, like `"E_CONFIG"`, or `"0"`, which would report success, also gives `1`.
+    pub fn exit_code(&self) -> i32 {
+        match self.code().and_then(|code| code.parse::<i32>().ok()) {
+            Some(code) if code != 0 => code,
+            _ => 1,
+        }
+    }
+
+    /// The message of the outermost frame which carries one, see `report_as`.
+    ///
+    /// When set, it is rendered instead of the leaf error by `Display` and the display adapters, like `to_markdown`,
+    /// so users see a summary while the real leaf stays reachable from `source()`.
+    /// `Debug`, `to_json_string` and the `serde` output keep the leaf error.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// use std::error::Error;
+    ///
+    /// let report = "x".parse::<u8>().report_as("the port must be a number").unwrap_err();
+    /// assert!(report.to_string().ends_with(" -> the port must be a number"));
+    /// assert_eq!(report.source().unwrap().to_string(), "invalid digit found in string");
+    /// assert!(format!("{:?}", report).ends_with(" -> ParseIntError { kind: InvalidDigit }"));
+    /// ```
+    pub fn message(&self) -> Option<&'static str> {
+        self.chain().find_map(|report| report.extra().message)
+    }
+
+    /// The `Location::caller()` of the conversion which created this frame, for tooling built around `core::panic::Location`.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// let report = "x".parse::<u8>().report().unwrap_err();
+    /// let location = report.location().unwrap();
+    /// assert_eq!((location.line(), location.column()), (report.line, report.column));
+    /// assert!(location.file().ends_with(report.file));
+    /// ```
+    ///
+    /// Its `file()` is the path exactly as the compiler gave it, while `Report::file` is usually made relative by `prepare!`.
+    /// It is `None` for a frame built with `Report::new`, and with the `redact_paths` option of `prepare!`,
+    /// which would otherwise leak the path.
+    pub fn location(&self) -> Option<&'static core::panic::Location<'static>> {
+        self.location
+    }
+
+    /// The concrete type of the leaf error, like `core::num::error::ParseIntError`, recorded when it was first reported.
+    ///
+    /// It is `None` when the innermost frame was built with `Report::new`, which only sees the boxed error.
+    pub fn leaf_type(&self) -> Option<&'static str> {
+        self.innermost().extra().leaf_type
+    }
+
+    /// Every link is an owned `Box<Report>` or `Box<SyncReport>`, which is what makes the chain finite and acyclic
+    fn chain(&self) -> impl Iterator<Item = &Report> + Clone {
+        core::iter::successors(Some(self), |report| {
+            let err = report.err.as_ref();
+            err.downcast_ref::<Report>()
+                .or_else(|| err.downcast_ref::<SyncReport>().map(|inner| &inner.0))
+        })
+    }
+
+    /// Moves `err` out, as `Report` implements `Drop`
+    fn take_err(&mut self) -> Box<dyn Error> {
+        core::mem::replace(&mut self.err, Box::new(Taken))
+    }
+
+    /// The report wrapping the leaf error
+    pub(crate) fn innermost(&self) -> &Report {
+        self.chain().last().unwrap_or(self)
+    }
+
+    pub(crate) fn frame(&self) -> Frame<'_> {
+        Frame {
+            pkg_name: self.pkg_name,
+            pkg_version: self.pkg_version,
+            file: self.file,
+            root: self.extra().root,
+            line: self.line,
+            column: self.column,
+            context: self.extra().context.as_deref(),
+            code: self.extra().code,
+            meta: &self.extra().meta,
+            module: self.extra().module,
+            #[cfg(feature = "time")]
+            timestamp: self.timestamp,
+            #[cfg(feature = "std")]
+            thread: self.thread.as_ref().map(FrameThread::Handle),
+            #[cfg(not(feature = "std"))]
+            thread: None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    pub use alloc::string::{String, ToString};
+    #[cfg(feature = "log")]
+    pub use log;
+
+    /// Emits `report` as a `tracing` error event with `pkg`, `file`, `line` and `cause` fields
+    #[cfg(feature = "tracing")]
+    pub fn trace_report(report: &Report) {
+        tracing::error!(
+            target: "erreport",
+            pkg = report.pkg_name,
+            file = report.file,
+            line = report.line,
+            cause = %report.root_cause(),
+            "{}",
+            report
+        );
+    }
+
+    /// Emits `report` as a `log` record carrying the file and line of its outermost frame
+    #[cfg(feature = "log")]
+    pub fn log_report(report: &Report, level: log::Level) {
+        let metadata = log::Metadata::builder()
+            .level(level)
+            .target(report.pkg_name)
+            .build();
+        if level <= log::max_level() && log::logger().enabled(&metadata) {
+            log::logger().log(
+                &log::Record::builder()
+                    .metadata(metadata)
+                    .args(format_args!("{}", report))
+                    .module_path(report.extra().module)
+                    .file(Some(report.file))
+                    .line(Some(report.line))
+                    .build(),
+            );
+        }
+    }
 
-        match index {
-            0 => {
-                format!(
-                    "{{{}@{}}} {}:{} -> {}",
-                    self.pkg_name, self.pkg_version, self.file, self.line, err_str
-                )
+    /// Builds the `Report` for a conversion generated by `prepare!`.
+    ///
+    /// It lives here rather than in the macro so erreport's own features decide which fields get filled.
+    pub fn capture(
+        pkg_name: &'static str,
+        pkg_version: &'static str,
+        paths: Paths,
+        loc: &'static core::panic::Location<'static>,
+        err: Box<dyn Error>,
+        leaf_type: &'static str,
+    ) -> Report {
+        let file = match paths {
+            Paths::Relative(root) => relative_file(loc.file(), root),
+            Paths::Absolute(_) => loc.file(),
+            Paths::Redacted => "<redacted>",
+        };
+        let innermost = !err.is::<Report>() && !err.is::<SyncReport>();
+        #[cfg(feature = "capture-leaf")]
+        let leaf_text = innermost.then(|| {
+            (
+                alloc::string::ToString::to_string(&err),
+                alloc::format!("{:?}", err),
+            )
+        });
+        let mut report = Report::new(pkg_name, pkg_version, file, loc.line(), err);
+        report.column = loc.column();
+        if innermost {
+            let extra = report.extra_mut();
+            extra.leaf_type = Some(leaf_type);
+            #[cfg(feature = "capture-leaf")]
+            if let Some((display, debug)) = leaf_text {
+                extra.leaf_display = Some(display);
+                extra.leaf_debug = Some(debug);
+            }
+        }
+        if !matches!(paths, Paths::Redacted) {
+            report.location = Some(loc);
+        }
+        if let Paths::Absolute(Some(manifest_dir)) = paths {
+            if !is_absolute(file) {
+                report.extra_mut().root = Some(source_root(file, manifest_dir));
             }
-            _ => {
-                format!("{}:{} -> {}", self.file, self.line, err_str)
+        }
+        #[cfg(feature = "std")]
+        scope::attach(&mut report);
+        report
+    }
+
+    /// Same as `capture`, for `report_sync`: the leaf being `Send + Sync` is what makes the `SyncReport` sound
+    pub fn capture_sync(
+        pkg_name: &'static str,
+        pkg_version: &'static str,
+        paths: Paths,
+        loc: &'static core::panic::Location<'static>,
+        err: Box<dyn Error + Send + Sync>,
+        leaf_type: &'static str,
+    ) -> SyncReport {
+        SyncReport(capture(pkg_name, pkg_version, paths, loc, err, leaf_type))
+    }
+
+    /// How `capture` turns `Location::file()` into `Report::file`
+    #[derive(Debug, Clone, Copy)]
+    pub enum Paths {
+        /// Strip this directory, `CARGO_MANIFEST_DIR` by default or `CARGO_WORKSPACE_DIR` with `workspace_paths`
+        Relative(&'static str),
+        /// Keep the path exactly as the compiler recorded it. With the crate's `CARGO_MANIFEST_DIR`, set by
+        /// `absolute_paths`, a relative one also gets the `root` it is under
+        Absolute(Option<&'static str>),
+        /// Record every file as `<redacted>`, set by `redact_paths`
+        Redacted,
+    }
+
+    /// Strips `manifest_dir` off `file`, but only if `file` really is inside it, otherwise `file` is kept as is.
+    ///
+    /// Both `/` and `\` are accepted as the separator after the prefix, so Windows paths don't keep a leading `\`,
+    /// and `manifest_dir` may end with one, like the `CARGO_WORKSPACE_DIR` set through `.cargo/config.toml`.
+    ///
+    /// Whatever the build setup, like vendored sources or a `file()` shorter than the manifest dir,
+    /// the result is always either the path under `manifest_dir` or `file` unchanged:
+    ///
+    /// ```
+    /// use erreport::__private::relative_file;
+    ///
+    /// assert_eq!(relative_file("/work/app/src/lib.rs", "/work/app"), "src/lib.rs");
+    /// assert_eq!(relative_file(r"C:\work\app\src\lib.rs", r"C:\work\app"), r"src\lib.rs");
+    /// assert_eq!(relative_file("/work/crates/foo/src/lib.rs", "/work/"), "crates/foo/src/lib.rs");
+    /// // Shorter than the manifest dir
+    /// assert_eq!(relative_file("src/lib.rs", "/work/app"), "src/lib.rs");
+    /// // Outside of it, even when the names share a prefix
+    /// assert_eq!(relative_file("/work/app2/src/lib.rs", "/work/app"), "/work/app2/src/lib.rs");
+    /// assert_eq!(relative_file("/work/app", "/work/app"), "/work/app");
+    /// // Exactly as long as the manifest dir, and one byte longer
+    /// assert_eq!(relative_file("/work/ap/", "/work/app"), "/work/ap/");
+    /// assert_eq!(relative_file("/work/app/", "/work/app"), "/work/app/");
+    /// // Inside of it, once with a multi-byte name
+    /// assert_eq!(relative_file("/work/app/src/main.rs", "/work/app"), "src/main.rs");
+    /// assert_eq!(relative_file("/work/äpp/src/lib.rs", "/work/äpp"), "src/lib.rs");
+    /// ```
+    ///
+    /// A stripped Windows path keeps its `\` separators, and frames render them as `/`:
+    ///
+    /// ```
+    /// use erreport::__private::relative_file;
+    ///
+    /// let file = relative_file(r"C:\work\app\src\db\pool.rs", r"C:\work\app\");
+    /// assert_eq!(file, r"src\db\pool.rs");
+    /// let report = erreport::Report::new("app", "0.1.0", file, 3, "boom");
+    /// assert_eq!(report.to_string(), "{app@0.1.0} src/db/pool.rs:3 -> boom");
+    /// ```
+    pub fn relative_file<'a>(file: &'a str, manifest_dir: &str) -> &'a str {
+        match file
+            .strip_prefix(manifest_dir.trim_end_matches(['/', '\\']))
+            .and_then(|rest| rest.strip_prefix(['/', '\\']))
+        {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => file,
+        }
+    }
+
+    /// Whether `file` is an absolute path, on Unix or on Windows
+    fn is_absolute(file: &str) -> bool {
+        file.starts_with(['/', '\\']) || file.as_bytes().get(1) == Some(&b':')
+    }
+
+    /// The directory a `file` recorded relative to the workspace root is under, given the `CARGO_MANIFEST_DIR`
+    /// of its crate: the manifest dir without the directories `file` starts with, if it ends with them.
+    ///
+    /// ```
+    /// use erreport::__private::source_root;
+    ///
+    /// // A workspace member, recorded relative to the workspace root
+    /// assert_eq!(source_root("crates/foo/src/lib.rs", "/work/crates/foo"), "/work");
+    /// // A single crate, which is its own workspace root
+    /// assert_eq!(source_root("src/main.rs", "/work/app"), "/work/app");
+    /// assert_eq!(source_root(r"crates\foo\src\lib.rs", r"C:\work\crates\foo\"), r"C:\work");
+    /// ```
+    pub fn source_root(file: &str, manifest_dir: &'static str) -> &'static str {
+        let manifest_dir = manifest_dir.trim_end_matches(['/', '\\']);
+        file.rmatch_indices(['/', '\\'])
+            .map(|(i, _)| &file[..i])
+            .find_map(|dirs| {
+                let root = manifest_dir.strip_suffix(dirs)?;
+                root.strip_suffix(['/', '\\'])
+                    .filter(|root| !root.is_empty())
+            })
+            .unwrap_or(manifest_dir)
+    }
+
+    /// Backs `assert_report_path!`
+    #[track_caller]
+    pub fn assert_report_path(report: &Report, expected: &[&str]) {
+        use core::fmt::Write;
+
+        let actual: Vec<String> = report
+            .frames()
+            .map(|frame| alloc::format!("{}:{}", frame.file, frame.line))
+            .collect();
+        if actual
+            .iter()
+            .map(String::as_str)
+            .eq(expected.iter().copied())
+        {
+            return;
+        }
+        let mut diff = String::new();
+        for i in 0..actual.len().max(expected.len()) {
+            match (expected.get(i), actual.get(i)) {
+                (Some(expected), Some(actual)) if expected == actual => {
+                    let _ = writeln!(diff, "  {}", actual);
+                }
+                (expected, actual) => {
+                    if let Some(expected) = expected {
+                        let _ = writeln!(diff, "- {}", expected);
+                    }
+                    if let Some(actual) = actual {
+                        let _ = writeln!(diff, "+ {}", actual);
+                    }
+                }
             }
         }
+        panic!(
+            "the report took another path (- expected, + actual):\n{}",
+            diff
+        );
+    }
+
+    pub fn msg(args: core::fmt::Arguments<'_>) -> MsgError {
+        MsgError(alloc::fmt::format(args))
+    }
+
+    /// The leaf of `report_msg!`: a `StrError` if the message is a plain string, otherwise a `MsgError`
+    pub fn msg_leaf(args: core::fmt::Arguments<'_>) -> Box<dyn Error> {
+        match args.as_str() {
+            Some(message) => Box::new(StrError(message)),
+            None => Box::new(msg(args)),
+        }
+    }
+
+    /// Converts an error into the boxed leaf of a `Report`.
+    ///
+    /// `M` only tells the impls apart, so types that don't implement `Error` (like `anyhow::Error`) get their own impl
+    /// without overlapping the blanket one. It is always inferred at the call site.
+    pub trait IntoLeaf<M> {
+        fn into_leaf(self) -> Box<dyn Error>;
+    }
+
+    pub enum StdError {}
+    impl<E: Error + 'static> IntoLeaf<StdError> for E {
+        fn into_leaf(self) -> Box<dyn Error> {
+            Box::new(self)
+        }
+    }
+
+    /// `Box<dyn Error>` doesn't implement `Error`, it is stored as the leaf as is
+    pub enum Boxed {}
+    impl IntoLeaf<Boxed> for Box<dyn Error> {
+        fn into_leaf(self) -> Box<dyn Error> {
+            self
+        }
+    }
+
+    pub enum BoxedSendSync {}
+    impl IntoLeaf<BoxedSendSync> for Box<dyn Error + Send + Sync> {
+        fn into_leaf(self) -> Box<dyn Error> {
+            self
+        }
+    }
+
+    /// The `Send + Sync` counterpart of `IntoLeaf`, for `report_sync`. It shares the markers of `IntoLeaf`,
+    /// so `report_sync` can reuse the `M` inferred for the generated trait.
+    pub trait IntoSyncLeaf<M> {
+        fn into_sync_leaf(self) -> Box<dyn Error + Send + Sync>;
+    }
+
+    impl<E: Error + Send + Sync + 'static> IntoSyncLeaf<StdError> for E {
+        fn into_sync_leaf(self) -> Box<dyn Error + Send + Sync> {
+            Box::new(self)
+        }
+    }
+
+    impl IntoSyncLeaf<BoxedSendSync> for Box<dyn Error + Send + Sync> {
+        fn into_sync_leaf(self) -> Box<dyn Error + Send + Sync> {
+            self
+        }
+    }
+
+    #[cfg(feature = "anyhow")]
+    impl IntoSyncLeaf<Anyhow> for anyhow::Error {
+        fn into_sync_leaf(self) -> Box<dyn Error + Send + Sync> {
+            Box::new(AnyhowError(self))
+        }
+    }
+
+    #[cfg(feature = "eyre")]
+    impl IntoSyncLeaf<Eyre> for eyre::Report {
+        fn into_sync_leaf(self) -> Box<dyn Error + Send + Sync> {
+            Box::new(EyreError(self))
+        }
+    }
+
+    /// Lets the generated `report_sync` require `E: IntoSyncLeaf<M>` through `Self`, as the trait doesn't name `E`
+    pub trait SyncResult<T, M> {
+        fn into_sync(self) -> core::result::Result<T, Box<dyn Error + Send + Sync>>;
+    }
+
+    impl<T, M, E: IntoSyncLeaf<M>> SyncResult<T, M> for core::result::Result<T, E> {
+        fn into_sync(self) -> core::result::Result<T, Box<dyn Error + Send + Sync>> {
+            self.map_err(IntoSyncLeaf::into_sync_leaf)
+        }
+    }
+
+    #[cfg(feature = "anyhow")]
+    pub enum Anyhow {}
+    #[cfg(feature = "anyhow")]
+    impl IntoLeaf<Anyhow> for anyhow::Error {
+        fn into_leaf(self) -> Box<dyn Error> {
+            Box::new(AnyhowError(self))
+        }
+    }
+
+    #[cfg(feature = "eyre")]
+    pub enum Eyre {}
+    #[cfg(feature = "eyre")]
+    impl IntoLeaf<Eyre> for eyre::Report {
+        fn into_leaf(self) -> Box<dyn Error> {
+            Box::new(EyreError(self))
+        }
     }
 }
 
+/// The leaf error stored by `ok_or_report` and `ok_or_report_with` when the `Option` is `None`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoneError(Option<String>);
+
+impl NoneError {
+    /// The generic error of `ok_or_report`
+    pub fn new() -> Self {
+        NoneError(None)
+    }
+
+    /// An error explaining what was expected, like `config key "port" missing`
+    pub fn with_message(message: impl Into<String>) -> Self {
+        NoneError(Some(message.into()))
+    }
+
+    /// The message given to `ok_or_report_with`, if any
+    pub fn message(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+impl core::fmt::Display for NoneError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.0 {
+            Some(message) => f.write_str(message),
+            None => write!(f, "called `ok_or_report` on a None value"),
+        }
+    }
+}
+impl Error for NoneError {}
+
+/// The leaf error created by `bail!`, holding just the formatted message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgError(String);
+
+impl core::fmt::Display for MsgError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl Error for MsgError {}
+
+/// The leaf error created by `report_msg!` for a message without arguments, holding it without copying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrError(&'static str);
+
+impl core::fmt::Display for StrError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+impl Error for StrError {}
+
 /// This will generate:
-/// 1. A type `pub(crate) type Report<T> = Result<T, erreport::Report>`
-/// 2. A trait called `pub(crate) trait ToReport<T>` to help to convert any `Result<T, E: std::error::Error>` to `Report`.
+/// 1. A type `pub(crate) type Report<T> = erreport::Result<T>`
+/// 2. A trait called `pub(crate) trait ToReport<T, M>` to help to convert any `Result<T, E: std::error::Error>` to `Report`.
+///    (`M` is an always-inferred marker which lets other error types, like `Box<dyn std::error::Error>` or `anyhow::Error`, be reported too.)
+/// 3. A trait called `pub(crate) trait OptionToReport<T>` to help to convert any `Option<T>` to `Report` with a `NoneError` leaf.
+///    `ok_or_report_with` gives the leaf a message:
+///
+/// ```
+/// # erreport::prepare!();
+/// use std::collections::HashMap;
+///
+/// let config: HashMap<&str, u16> = HashMap::new();
+/// let report = config.get("port").ok_or_report_with(r#"config key "port" missing"#).unwrap_err();
+/// assert!(report.to_string().ends_with(r#" -> config key "port" missing"#));
+/// let leaf = report.downcast_source_ref::<erreport::NoneError>().unwrap();
+/// assert_eq!(leaf.message(), Some(r#"config key "port" missing"#));
+/// ```
+/// 4. A trait called `pub(crate) trait ReportContext` with `with_context`, which wraps a caught `erreport::Report`
+///    in a new frame at the caller, carrying a context:
+///
+/// ```
+/// # erreport::prepare!();
+/// let report = "x".parse::<u8>().report().unwrap_err();
+/// let report = report.with_context("while loading the config");
+/// assert_eq!(report.depth(), 2);
+/// assert_eq!(report.entry_frame().context, Some("while loading the config"));
+/// assert_eq!(report.entry_frame().line, line!() - 3);
+/// ```
+///
+/// The visibility of all traits can be set with `prepare!(pub)`, e.g. to re-export them from a utility crate.
+/// Note that the frames always record the package which invoked `prepare!`.
+///
+/// `ToReport` can be renamed with `prepare!(MyReportExt)` or `prepare!(pub MyReportExt)` to avoid a name clash.
+/// Only the trait name changes, the methods keep their names so call sites stay the same.
+///
+/// `OptionToReport`, `ReportContext` and the `Report` alias keep their names, so two `prepare!` still can't share a module:
+///
+/// ```compile_fail
+/// erreport::prepare!(FirstExt);
+/// erreport::prepare!(SecondExt);
+/// ```
+///
+/// To keep a `ReportContext` of your own, invoke `prepare!` in a module of its own and import only what you use:
+///
+/// ```
+/// mod errors {
+///     erreport::prepare!(pub);
+/// }
+/// use errors::ToReport;
+///
+/// trait ReportContext {}
+///
+/// assert!("x".parse::<u8>().report().is_err());
+/// ```
+///
+/// `report_with` formats its context only on `Err`, but the argument itself is evaluated first.
+/// When it is expensive to build, `report_with_ctx` takes a closure which is only called on `Err`:
+///
+/// ```
+/// # erreport::prepare!();
+/// fn load(path: &str) -> Report<u8> {
+///     path.parse::<u8>().report_with_ctx(|| format!("loading {}", path))
+/// }
+///
+/// let value = "7".parse::<u8>().report_with_ctx(|| unreachable!("not called on `Ok`"));
+/// assert_eq!(value.unwrap(), 7);
+/// assert!(load("x").unwrap_err().to_string().contains("(loading x)"));
+/// ```
+///
+/// Options can follow, separated by commas, like `prepare!(pub MyReportExt, absolute_paths)`:
+/// * `absolute_paths`: keep `Location::file()` verbatim instead of stripping `CARGO_MANIFEST_DIR`.
+///   Dependencies outside of the workspace then render with their full path, like `/home/me/.cargo/registry/src/...`.
+///   The compiler records the files of workspace members relative to the workspace root, so those frames also
+///   keep the root they are under, worked out from `CARGO_MANIFEST_DIR`, in `ReportExtra::root`, and render
+///   as `{root}/{file}`. Either way the mode shows in the output:
+///
+/// ```
+/// mod relative {
+///     erreport::prepare!();
+///     pub fn report() -> erreport::Report {
+///         "x".parse::<u8>().report().unwrap_err()
+///     }
+/// }
+/// mod absolute {
+///     erreport::prepare!(absolute_paths);
+///     pub fn report() -> erreport::Report {
+///         "x".parse::<u8>().report().unwrap_err()
+///     }
+/// }
+///
+/// let (relative, absolute) = (relative::report(), absolute::report());
+/// assert!(!relative.to_string().contains(env!("CARGO_MANIFEST_DIR")));
+/// assert!(absolute.to_string().contains(env!("CARGO_MANIFEST_DIR")));
+/// assert_ne!(relative.to_string(), absolute.to_string());
+/// ```
+///
+/// * `workspace_paths`: strip the `CARGO_WORKSPACE_DIR` environment variable instead of `CARGO_MANIFEST_DIR`, when it is set
+///   at build time, so every member renders like `crates/foo/src/lib.rs` and two `src/lib.rs` can't be confused.
+///   Cargo doesn't set it, add `CARGO_WORKSPACE_DIR = { value = "", relative = true }` to the `[env]` table of the
+///   workspace's `.cargo/config.toml`. Without it the paths stay relative to the crate, as by default.
+///   This matters once the compiler records absolute paths, like for a workspace used as a path dependency of another one:
+///   built from inside the workspace, paths are already relative to its root.
+///
+/// ```
+/// erreport::prepare!(workspace_paths);
+///
+/// let report = "x".parse::<u8>().report().unwrap_err();
+/// assert!(!report.file.starts_with('/'));
+/// ```
+///
+/// * `redact_paths`: record every file as `<redacted>`, keeping the line and column, so no source paths of this crate
+///   show up in its reports. Other crates, and their frames in the same chain, keep their own setting.
+///   The compiler still embeds the paths in the binary for `Location`; use `--remap-path-prefix` to hide them there too.
+///
+/// ```
+/// erreport::prepare!(redact_paths);
+///
+/// let report = "x".parse::<u8>().report().unwrap_err();
+/// assert_eq!(report.file, "<redacted>");
+/// assert!(report.to_string().contains(" <redacted>:"));
+/// assert!(!report.to_string().contains(".rs"));
+/// assert!(!format!("{:?}", report).contains(".rs"));
+/// assert!(report.location().is_none());
+/// ```
+///
+/// * `separator = " » "`: the separator used by `ReportContext::display`, fixed for the whole crate at no runtime cost.
+///   `Display` itself always uses `" -> "`.
+///
+/// ```
+/// erreport::prepare!(separator = " » ");
+///
+/// let report = "x".parse::<u8>().report().unwrap_err();
+/// let report = report.with_context("loading");
+/// assert!(report.display().to_string().contains(" » "));
+/// assert!(!report.display().to_string().contains(" -> "));
+/// ```
+///
+/// * `free_functions`: generate free functions taking the `Result` or `Option` as their first argument,
+///   like `report(result)` or `ok_or_report_with(option, "..")`, instead of the traits. No trait is exposed,
+///   so a library doesn't hand its blanket impls to whoever imports it, at the cost of method syntax:
+///   the macros which call methods, like `report!` or `bail!`, and the `log`/`tracing` variants, are not available.
+///
+/// ```
+/// mod errors {
+///     erreport::prepare!(pub, free_functions);
+/// }
+///
+/// fn parse(s: &str) -> errors::Report<u8> {
+///     let value = errors::report_with(s.parse::<u8>(), "parsing")?;
+///     errors::ok_or_report(value.checked_mul(2))
+/// }
+///
+/// assert_eq!(parse("4").unwrap(), 8);
+/// assert!(parse("x").unwrap_err().to_string().contains("(parsing)"));
+/// ```
 #[macro_export]
 macro_rules! prepare {
-    () => {
-        pub(crate) type Report<T> = Result<T, erreport::Report>;
+    (@gen [$($vis:tt)*] [$name:ident] [$($paths:tt)*] [$separator:expr]) => {
+        pub(crate) type Report<T> = erreport::Result<T>;
 
-        pub(crate) trait ToReport<T> {
-            fn report(self) -> Result<T, erreport::Report>;
+        $($vis)* trait $name<T, M> {
+            fn report(self) -> erreport::Result<T>;
+            /// Same as `report`, but also records what was being done at this point
+            fn report_with(self, context: impl core::fmt::Display) -> erreport::Result<T>;
+            /// Same as `report_with`, but the context is only built by `context()` when there is an error
+            fn report_with_ctx(self, context: impl FnOnce() -> erreport::__private::String) -> erreport::Result<T>;
+            /// Same as `report`, but also tags this point with a stable error code, read back by `Report::code`
+            fn report_coded(self, code: &'static str) -> erreport::Result<T>;
+            /// Same as `report`, but `Display` renders `message` instead of the error, which stays the `source()`
+            fn report_as(self, message: &'static str) -> erreport::Result<T>;
+            /// Same as `report`, but also attaches key-value data to this point, rendered like `src/lib.rs:42:5 {user_id=42 retry=3}`
+            fn report_meta(self, kvs: &[(&'static str, impl core::fmt::Display)]) -> erreport::Result<T>;
+            /// Same as `report`, but gives a `Send + Sync` `SyncReport`, only for errors which are `Send + Sync` too
+            fn report_sync(self) -> erreport::Result<T, erreport::SyncReport>
+            where
+                Self: erreport::__private::SyncResult<T, M>;
+            #[doc(hidden)]
+            fn __report_in(self, module: &'static str) -> erreport::Result<T>;
+            erreport::__report_logged!(decl);
+            erreport::__report_traced!(decl);
         }
 
-        impl<T, E: std::error::Error + 'static> ToReport<T> for Result<T, E> {
+        impl<T, M, E: erreport::__private::IntoLeaf<M>> $name<T, M> for core::result::Result<T, E> {
             #[track_caller]
-            fn report(self) -> Result<T, erreport::Report> {
+            fn report(self) -> erreport::Result<T> {
                 match self {
                     Ok(t) => Ok(t),
-                    Err(err) => {
-                        let loc = core::panic::Location::caller();
-                        Err(erreport::Report {
-                            pkg_name: env!("CARGO_PKG_NAME"),
-                            pkg_version: env!("CARGO_PKG_VERSION"),
-                            file: loc
-                                .file()
-                                .get(env!("CARGO_MANIFEST_DIR").len() + 1..)
-                                .unwrap_or(loc.file()),
-                            line: loc.line(),
-                            err: err.into(),
-                        })
-                    }
+                    Err(err) => Err(erreport::__private::capture(
+                        env!("CARGO_PKG_NAME"),
+                        env!("CARGO_PKG_VERSION"),
+                        $($paths)*,
+                        core::panic::Location::caller(),
+                        err.into_leaf(),
+                        core::any::type_name::<E>(),
+                    )),
+                }
+            }
+
+            #[track_caller]
+            fn report_with(self, context: impl core::fmt::Display) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().context = Some(erreport::__private::ToString::to_string(&context));
+                    report
+                })
+            }
+
+            #[track_caller]
+            fn report_with_ctx(self, context: impl FnOnce() -> erreport::__private::String) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().context = Some(context());
+                    report
+                })
+            }
+
+            #[track_caller]
+            fn report_coded(self, code: &'static str) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().code = Some(code);
+                    report
+                })
+            }
+
+            #[track_caller]
+            fn report_as(self, message: &'static str) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().message = Some(message);
+                    report
+                })
+            }
+
+            #[track_caller]
+            fn report_meta(self, kvs: &[(&'static str, impl core::fmt::Display)]) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().meta.extend(
+                        kvs.iter()
+                            .map(|(key, value)| (*key, erreport::__private::ToString::to_string(value))),
+                    );
+                    report
+                })
+            }
+
+            #[track_caller]
+            fn report_sync(self) -> erreport::Result<T, erreport::SyncReport>
+            where
+                Self: erreport::__private::SyncResult<T, M>,
+            {
+                match erreport::__private::SyncResult::into_sync(self) {
+                    Ok(t) => Ok(t),
+                    Err(err) => Err(erreport::__private::capture_sync(
+                        env!("CARGO_PKG_NAME"),
+                        env!("CARGO_PKG_VERSION"),
+                        $($paths)*,
+                        core::panic::Location::caller(),
+                        err,
+                        core::any::type_name::<E>(),
+                    )),
+                }
+            }
+
+            #[track_caller]
+            fn __report_in(self, module: &'static str) -> erreport::Result<T> {
+                $name::report(self).map_err(|mut report| {
+                    report.extra_mut().module = Some(module);
+                    report
+                })
+            }
+
+            erreport::__report_logged!(impl $name);
+            erreport::__report_traced!(impl $name);
+        }
+
+        $($vis)* trait OptionToReport<T> {
+            fn ok_or_report(self) -> erreport::Result<T>;
+            /// Same as `ok_or_report`, but the `NoneError` says what was expected. It is only formatted on `None`
+            fn ok_or_report_with(self, message: impl core::fmt::Display) -> erreport::Result<T>;
+        }
+
+        impl<T> OptionToReport<T> for Option<T> {
+            #[track_caller]
+            fn ok_or_report(self) -> erreport::Result<T> {
+                $name::report(self.ok_or_else(erreport::NoneError::new))
+            }
+
+            #[track_caller]
+            fn ok_or_report_with(self, message: impl core::fmt::Display) -> erreport::Result<T> {
+                $name::report(self.ok_or_else(|| {
+                    erreport::NoneError::with_message(erreport::__private::ToString::to_string(&message))
+                }))
+            }
+        }
+
+        $($vis)* trait ReportContext {
+            fn with_context(self, context: impl core::fmt::Display) -> erreport::Report;
+            /// Renders with the `separator` given to `prepare!`, `" -> "` by default
+            fn display(&self) -> erreport::ReportDisplay<'_>;
+        }
+
+        impl ReportContext for erreport::Report {
+            #[track_caller]
+            fn with_context(self, context: impl core::fmt::Display) -> erreport::Report {
+                match $name::report_with(core::result::Result::<(), _>::Err(self), context) {
+                    Ok(()) => unreachable!(),
+                    Err(report) => report,
                 }
             }
+
+            fn display(&self) -> erreport::ReportDisplay<'_> {
+                self.display_with($separator)
+            }
+        }
+    };
+    (@gen_fns [$($vis:tt)*] $paths:tt $separator:tt) => {
+        pub(crate) type Report<T> = erreport::Result<T>;
+
+        #[allow(dead_code)]
+        mod __erreport_generated {
+            $crate::prepare!(@gen [pub(crate)] [ToReport] $paths $separator);
         }
+
+        /// Converts `result` into a `Report` at the caller, like `ToReport::report`
+        #[track_caller]
+        $($vis)* fn report<T, M, E: erreport::__private::IntoLeaf<M>>(result: core::result::Result<T, E>) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report(result)
+        }
+
+        /// Like `ToReport::report_with`
+        #[track_caller]
+        $($vis)* fn report_with<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+            context: impl core::fmt::Display,
+        ) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report_with(result, context)
+        }
+
+        /// Like `ToReport::report_with_ctx`
+        #[track_caller]
+        $($vis)* fn report_with_ctx<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+            context: impl FnOnce() -> erreport::__private::String,
+        ) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report_with_ctx(result, context)
+        }
+
+        /// Like `ToReport::report_coded`
+        #[track_caller]
+        $($vis)* fn report_coded<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+            code: &'static str,
+        ) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report_coded(result, code)
+        }
+
+        /// Like `ToReport::report_as`
+        #[track_caller]
+        $($vis)* fn report_as<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+            message: &'static str,
+        ) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report_as(result, message)
+        }
+
+        /// Like `ToReport::report_meta`
+        #[track_caller]
+        $($vis)* fn report_meta<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+            kvs: &[(&'static str, impl core::fmt::Display)],
+        ) -> erreport::Result<T> {
+            __erreport_generated::ToReport::report_meta(result, kvs)
+        }
+
+        /// Like `ToReport::report_sync`
+        #[track_caller]
+        $($vis)* fn report_sync<T, M, E: erreport::__private::IntoLeaf<M>>(
+            result: core::result::Result<T, E>,
+        ) -> erreport::Result<T, erreport::SyncReport>
+        where
+            core::result::Result<T, E>: erreport::__private::SyncResult<T, M>,
+        {
+            __erreport_generated::ToReport::report_sync(result)
+        }
+
+        /// Like `OptionToReport::ok_or_report`
+        #[track_caller]
+        $($vis)* fn ok_or_report<T>(option: Option<T>) -> erreport::Result<T> {
+            __erreport_generated::OptionToReport::ok_or_report(option)
+        }
+
+        /// Like `OptionToReport::ok_or_report_with`
+        #[track_caller]
+        $($vis)* fn ok_or_report_with<T>(option: Option<T>, message: impl core::fmt::Display) -> erreport::Result<T> {
+            __erreport_generated::OptionToReport::ok_or_report_with(option, message)
+        }
+
+        /// Like `ReportContext::with_context`
+        #[track_caller]
+        $($vis)* fn with_context(report: erreport::Report, context: impl core::fmt::Display) -> erreport::Report {
+            __erreport_generated::ReportContext::with_context(report, context)
+        }
+
+        /// Like `ReportContext::display`
+        $($vis)* fn display(report: &erreport::Report) -> erreport::ReportDisplay<'_> {
+            __erreport_generated::ReportContext::display(report)
+        }
+    };
+    // Parses `[vis] [Name] [, option]*` into `@gen [vis] [Name] [paths] [separator]`, or `@gen_fns` for `free_functions`
+    (@vis pub ($($restricted:tt)*) $($rest:tt)*) => {
+        $crate::prepare!(@name [pub ($($restricted)*)] $($rest)*);
+    };
+    (@vis pub $($rest:tt)*) => {
+        $crate::prepare!(@name [pub] $($rest)*);
+    };
+    (@vis $($rest:tt)*) => {
+        $crate::prepare!(@name [pub(crate)] $($rest)*);
+    };
+    (@name $vis:tt absolute_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] absolute_paths $($rest)*);
+    };
+    (@name $vis:tt workspace_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] workspace_paths $($rest)*);
+    };
+    (@name $vis:tt redact_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] redact_paths $($rest)*);
+    };
+    (@name $vis:tt separator $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] separator $($rest)*);
+    };
+    (@name $vis:tt free_functions $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] free_functions $($rest)*);
+    };
+    (@name $vis:tt $name:ident $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [$name] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] $($rest)*);
+    };
+    (@name $vis:tt $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis [ToReport] [erreport::__private::Paths::Relative(env!("CARGO_MANIFEST_DIR"))] [" -> "] [trait] $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt , $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name $paths $separator $mode $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt absolute_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name [erreport::__private::Paths::Absolute(Some(env!("CARGO_MANIFEST_DIR")))] $separator $mode $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt workspace_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name [erreport::__private::Paths::Relative(match option_env!("CARGO_WORKSPACE_DIR") {
+            Some(dir) => dir,
+            None => env!("CARGO_MANIFEST_DIR"),
+        })] $separator $mode $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt redact_paths $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name [erreport::__private::Paths::Redacted] $separator $mode $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt separator = $value:literal $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name $paths [$value] $mode $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt $mode:tt free_functions $($rest:tt)*) => {
+        $crate::prepare!(@opts $vis $name $paths $separator [functions] $($rest)*);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt [trait]) => {
+        $crate::prepare!(@gen $vis $name $paths $separator);
+    };
+    (@opts $vis:tt $name:tt $paths:tt $separator:tt [functions]) => {
+        $crate::prepare!(@gen_fns $vis $paths $separator);
+    };
+    ($($args:tt)*) => {
+        $crate::prepare!(@vis $($args)*);
+    };
+}
+
+/// Generates `report_logged`, which also emits the `Report` as a `log` record, only with the `log` feature.
+///
+/// The record has the outermost frame's file and line, and is skipped below `log::max_level()`:
+///
+/// ```
+/// # erreport::prepare!();
+/// use std::sync::Mutex;
+///
+/// static RECORDS: Mutex<Vec<(log::Level, String, Option<u32>)>> = Mutex::new(Vec::new());
+///
+/// struct Capture;
+/// impl log::Log for Capture {
+///     fn enabled(&self, _: &log::Metadata) -> bool {
+///         true
+///     }
+///     fn log(&self, record: &log::Record) {
+///         RECORDS.lock().unwrap().push((record.level(), record.args().to_string(), record.line()));
+///     }
+///     fn flush(&self) {}
+/// }
+///
+/// log::set_logger(&Capture).unwrap();
+/// log::set_max_level(log::LevelFilter::Warn);
+///
+/// let report = "x".parse::<u8>().report_logged(log::Level::Warn).unwrap_err();
+/// assert_eq!(*RECORDS.lock().unwrap(), [(log::Level::Warn, report.to_string(), Some(report.line))]);
+///
+/// assert!("x".parse::<u8>().report_logged(log::Level::Info).is_err());
+/// assert_eq!(RECORDS.lock().unwrap().len(), 1);
+/// ```
+#[cfg(feature = "log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __report_logged {
+    (decl) => {
+        /// Same as `report`, but also emits the `Report` as a `log` record at `level`
+        fn report_logged(self, level: $crate::__private::log::Level) -> $crate::Result<T>;
+    };
+    (impl $name:ident) => {
+        #[track_caller]
+        fn report_logged(self, level: $crate::__private::log::Level) -> $crate::Result<T> {
+            $name::report(self).inspect_err(|report| $crate::__private::log_report(report, level))
+        }
+    };
+}
+#[cfg(not(feature = "log"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __report_logged {
+    ($($tt:tt)*) => {};
+}
+
+/// Generates `report_traced`, which also emits the `Report` as a `tracing` event, only with the `tracing` feature.
+///
+/// The event is an error with the `erreport` target:
+///
+/// ```
+/// # erreport::prepare!();
+/// use std::fmt::Debug;
+/// use std::sync::Mutex;
+/// use tracing::field::{Field, Visit};
+/// use tracing::span::{Attributes, Id, Record};
+/// use tracing::{Event, Level, Metadata};
+///
+/// static EVENTS: Mutex<Vec<(Level, &str, Vec<String>)>> = Mutex::new(Vec::new());
+///
+/// struct Fields(Vec<String>);
+/// impl Visit for Fields {
+///     fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+///         self.0.push(format!("{}={:?}", field.name(), value));
+///     }
+/// }
+///
+/// struct Capture;
+/// impl tracing::Subscriber for Capture {
+///     fn enabled(&self, _: &Metadata<'_>) -> bool {
+///         true
+///     }
+///     fn new_span(&self, _: &Attributes<'_>) -> Id {
+///         Id::from_u64(1)
+///     }
+///     fn record(&self, _: &Id, _: &Record<'_>) {}
+///     fn record_follows_from(&self, _: &Id, _: &Id) {}
+///     fn event(&self, event: &Event<'_>) {
+///         let mut fields = Fields(Vec::new());
+///         event.record(&mut fields);
+///         let metadata = event.metadata();
+///         EVENTS.lock().unwrap().push((*metadata.level(), metadata.target(), fields.0));
+///     }
+///     fn enter(&self, _: &Id) {}
+///     fn exit(&self, _: &Id) {}
+/// }
+///
+/// let report = tracing::subscriber::with_default(Capture, || "x".parse::<u8>().report_traced().unwrap_err());
+/// assert_eq!(
+///     *EVENTS.lock().unwrap(),
+///     [(
+///         Level::ERROR,
+///         "erreport",
+///         vec![
+///             format!("message={}", report),
+///             "pkg=\"erreport\"".to_string(),
+///             format!("file={:?}", report.file),
+///             format!("line={}", report.line),
+///             "cause=invalid digit found in string".to_string(),
+///         ]
+///     )]
+/// );
+/// ```
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __report_traced {
+    (decl) => {
+        /// Same as `report`, but also emits a `tracing::error!` event with `pkg`, `file`, `line` and `cause` fields
+        fn report_traced(self) -> $crate::Result<T>;
+    };
+    (impl $name:ident) => {
+        #[track_caller]
+        fn report_traced(self) -> $crate::Result<T> {
+            $name::report(self).inspect_err($crate::__private::trace_report)
+        }
+    };
+}
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __report_traced {
+    ($($tt:tt)*) => {};
+}
+
+/// Same as calling [`.report()`](crate#the-generated-trait) on a `Result`, but also records the `module_path!()`
+/// of the call site, rendered as `{pkg@ver} crate::db::pool src/db.rs:42:5 -> ...`.
+///
+/// ```
+/// # erreport::prepare!();
+/// mod db {
+///     use super::*;
+///
+///     pub fn port() -> Report<u16> {
+///         erreport::report!("x".parse::<u16>())
+///     }
+/// }
+///
+/// # fn main() {
+/// let report = db::port().unwrap_err();
+/// let module = report.extra().module.unwrap();
+/// assert!(module.ends_with("::db"));
+/// assert!(report.to_string().contains(&format!(" {} {}:", module, report.file)));
+/// assert!(report.leaf_is::<std::num::ParseIntError>());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! report {
+    ($result:expr) => {
+        $result.__report_in(module_path!())
+    };
+}
+
+/// Same as calling [`.report_with(..)`](crate#the-generated-trait) on a `Result`, but takes the context as `format!` arguments.
+///
+/// ```
+/// # erreport::prepare!();
+/// fn load(path: &str) -> Report<u8> {
+///     erreport::report_with!(path.parse::<u8>(), "loading {}", path)
+/// }
+///
+/// assert_eq!(load("7").unwrap(), 7);
+/// assert!(load("x").unwrap_err().to_string().contains("(loading x)"));
+/// ```
+///
+/// No `String` is built: the message is only formatted when the `Err` is converted.
+#[macro_export]
+macro_rules! report_with {
+    ($result:expr, $($arg:tt)+) => {
+        $result.report_with(core::format_args!($($arg)+))
+    };
+}
+
+/// Builds a `Report` at the call site for an error without an underlying cause, like `return Err(report_msg!("boom"))`,
+/// through [`.report()`](crate#the-generated-trait).
+///
+/// ```
+/// # erreport::prepare!();
+/// use std::error::Error;
+///
+/// fn connect(retries: u8) -> Report<()> {
+///     if retries == 0 {
+///         return Err(erreport::report_msg!("no retries left"));
+///     }
+///     Err(erreport::report_msg!("gave up after {} retries", retries))
+/// }
+///
+/// let report = connect(0).unwrap_err();
+/// assert!(report.to_string().ends_with(" -> no retries left"));
+/// assert!(report.source().unwrap().is::<erreport::StrError>());
+/// assert!(connect(3).unwrap_err().leaf_is::<erreport::MsgError>());
+/// ```
+///
+/// A plain string literal becomes a `StrError` leaf which borrows it, so the message isn't copied to the heap.
+/// With format arguments the leaf is a `MsgError`, like for `bail!`.
+#[macro_export]
+macro_rules! report_msg {
+    ($($arg:tt)+) => {
+        core::result::Result::<(), _>::Err($crate::__private::msg_leaf(core::format_args!($($arg)+)))
+            .report()
+            .unwrap_err()
+    };
+}
+
+/// Returns early with a `Report`, built through [`.report()`](crate#the-generated-trait), whose leaf is a `MsgError`
+/// holding the formatted message.
+///
+/// ```
+/// # erreport::prepare!();
+/// fn check(port: u16) -> Report<()> {
+///     if port < 1024 {
+///         erreport::bail!("port {} is reserved", port);
+///     }
+///     Ok(())
+/// }
+///
+/// assert!(check(8080).is_ok());
+/// let report = check(80).unwrap_err();
+/// assert!(report.to_string().ends_with(" -> port 80 is reserved"));
+/// assert!(report.leaf_is::<erreport::MsgError>());
+/// assert_eq!(report.line, line!() - 9); // the line of the `bail!`
+/// ```
+///
+/// The frame records the location of the `bail!` call, and the function may return any error type which implements
+/// `From<erreport::Report>`.
+#[macro_export]
+macro_rules! bail {
+    ($($arg:tt)+) => {
+        return core::result::Result::Err(core::convert::From::from(
+            core::result::Result::<(), _>::Err($crate::__private::msg(core::format_args!($($arg)+)))
+                .report()
+                .unwrap_err(),
+        ))
+    };
+}
+
+/// Builds a `Report` at the call site, through [`.report()`](crate#the-generated-trait), whose leaf is an
+/// `AggregateError` of the given `Vec<Box<dyn Error>>`.
+///
+/// ```
+/// # erreport::prepare!();
+/// fn validate(name: &str, age: &str) -> Report<()> {
+///     let mut errors: Vec<Box<dyn std::error::Error>> = Vec::new();
+///     if let Err(err) = age.parse::<u8>() {
+///         errors.push(Box::new(err));
+///     }
+///     if name.is_empty() {
+///         errors.push("name is empty".into());
+///     }
+///     if !errors.is_empty() {
+///         return Err(erreport::aggregate!(errors));
+///     }
+///     Ok(())
+/// }
+///
+/// let report = validate("", "x").unwrap_err();
+/// assert!(report.to_string().ends_with("2 errors: [invalid digit found in string; name is empty]"));
+/// ```
+///
+/// Like any leaf, the `AggregateError` is what the report's `source()` returns, so the first error is one level
+/// further, at `source().source()`. The frames end at the aggregate, the reports inside it are not walked:
+///
+/// ```
+/// # erreport::prepare!();
+/// use std::error::Error;
+///
+/// let inner = "x".parse::<u8>().report().report().unwrap_err();
+/// let report = erreport::aggregate!(vec![Box::new(inner) as Box<dyn Error>, "name is empty".into()]);
+/// assert_eq!(report.depth(), 1);
+/// assert_eq!(report.frames().count(), 1);
+/// let aggregate = report.source().unwrap();
+/// assert!(aggregate.is::<erreport::AggregateError>());
+/// let first = aggregate.source().unwrap().downcast_ref::<erreport::Report>().unwrap();
+/// assert_eq!(first.depth(), 2);
+/// ```
+#[macro_export]
+macro_rules! aggregate {
+    ($errors:expr) => {
+        core::result::Result::<(), _>::Err($crate::AggregateError::new($errors))
+            .report()
+            .unwrap_err()
+    };
+}
+
+/// Returns early like `bail!` when `cond` is false, otherwise does nothing.
+///
+/// ```
+/// # erreport::prepare!();
+/// fn check(port: u16) -> Report<()> {
+///     erreport::ensure!(port >= 1024, "port {} is reserved", port);
+///     erreport::ensure!(port != 8080);
+///     Ok(())
+/// }
+///
+/// assert!(check(3000).is_ok());
+/// assert!(check(80).unwrap_err().to_string().ends_with(" -> port 80 is reserved"));
+/// let report = check(8080).unwrap_err();
+/// assert!(report.to_string().ends_with(" -> condition failed: `port != 8080`"));
+/// assert!(report.leaf_is::<erreport::MsgError>());
+/// ```
+///
+/// The message is only formatted when the check fails. Without one, it reads ``condition failed: `port != 8080` ``.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        $crate::ensure!($cond, "{}", core::concat!("condition failed: `", core::stringify!($cond), "`"))
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::bail!($($arg)+);
+        }
+    };
+}
+
+/// Asserts that the frames of a `Report` are at exactly these `file:line` locations, outermost first.
+///
+/// The `{pkg@ver}` headers, columns and the leaf error are not compared. On a mismatch it panics with a diff:
+///
+/// ```
+/// let report = erreport::Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+/// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, report);
+/// erreport::assert_report_path!(report, ["src/main.rs:10", "src/lib.rs:7"]);
+///
+/// let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///     erreport::assert_report_path!(report, ["src/main.rs:10", "src/lib.rs:8"]);
+/// }))
+/// .unwrap_err();
+/// assert_eq!(
+///     payload.downcast_ref::<String>().unwrap(),
+///     "the report took another path (- expected, + actual):\n  src/main.rs:10\n- src/lib.rs:8\n+ src/lib.rs:7\n"
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_report_path {
+    ($report:expr, $expected:expr $(,)?) => {
+        $crate::__private::assert_report_path(&$report, &$expected)
     };
 }
@@ -0,0 +1,81 @@
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+use crate::{display, Frame, Report};
+
+impl Report {
+    /// Renders the chain as Markdown, to paste into an issue.
+    ///
+    /// The first `{pkg@ver}` becomes a bold header, the frames a numbered list, and the leaf error a code span.
+    /// A frame in another package than the one before it starts with that package in bold:
+    ///
+    /// ```
+    /// let mut report = erreport::Report::new("dep", "1.2.0", "src/lib.rs", 7, "invalid digit");
+    /// report.extra_mut().context = Some("parsing the port".into());
+    /// let report = erreport::Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// assert_eq!(
+    ///     report.to_markdown(),
+    ///     "**app@0.1.0**\n\
+    ///      \n\
+    ///      1. `src/main.rs:10`\n\
+    ///      2. **dep@1.2.0** `src/lib.rs:7` (parsing the port)\n\
+    ///      \n\
+    ///      Cause: `invalid digit`"
+    /// );
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let mut prev: Option<Frame<'_>> = None;
+        for (i, frame) in self.frames().enumerate() {
+            match prev {
+                None => {
+                    let _ = writeln!(out, "**{}@{}**\n", frame.pkg_name, frame.pkg_version);
+                    let _ = write!(out, "{}. ", i + 1);
+                }
+                Some(prev) if prev.same_package(&frame) => {
+                    let _ = write!(out, "{}. ", i + 1);
+                }
+                Some(_) => {
+                    let _ = write!(
+                        out,
+                        "{}. **{}@{}** ",
+                        i + 1,
+                        frame.pkg_name,
+                        frame.pkg_version
+                    );
+                }
+            }
+            let location = alloc::format!(
+                "{}{}",
+                frame.file.replace('\\', "/"),
+                display::LineColumn(&frame)
+            );
+            push_code_span(&mut out, &location);
+            if let Some(context) = frame.context {
+                let _ = write!(out, " ({})", context);
+            }
+            out.push('\n');
+            prev = Some(frame);
+        }
+        out.push_str("\nCause: ");
+        push_code_span(&mut out, &display::Leaf(self).to_string());
+        out
+    }
+}
+
+/// Wraps `text` in enough backticks that the ones inside it don't end the span early
+fn push_code_span(out: &mut String, text: &str) {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    let _ = write!(out, "{}{}{}{}{}", fence, pad, text, pad, fence);
+}
@@ -0,0 +1,30 @@
+use alloc::boxed::Box;
+use core::error::Error;
+use std::sync::Once;
+
+use crate::ReportSnapshot;
+
+/// Installs a panic hook which prints the whole chain to stderr when a panic carries a report, then runs the previous hook.
+///
+/// `Report` itself is not `Send`, so it can't be a panic payload; panic with a snapshot instead, like
+/// `std::panic::panic_any(report.snapshot())`. A `Box<dyn Error + Send + Sync>` payload is printed with its `Display`.
+/// Other payloads only go to the previous hook. Note that `unwrap()` on a `Result<_, Report>` already panics with
+/// the rendered chain in its message, so it doesn't need the hook.
+///
+/// The hook is process-global: it wraps whatever hook is installed at the time of the first call, and later calls do nothing.
+/// To remove it, `std::panic::take_hook` drops it together with the hook it wraps.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            if let Some(snapshot) = payload.downcast_ref::<ReportSnapshot>() {
+                eprintln!("{}", snapshot);
+            } else if let Some(err) = payload.downcast_ref::<Box<dyn Error + Send + Sync>>() {
+                eprintln!("{}", err);
+            }
+            prev(info);
+        }));
+    });
+}
@@ -0,0 +1,22 @@
+//! Everything needed to work with reports, for a single `use erreport::prelude::*;`
+//!
+//! It brings in:
+//! * the `prepare!`, `report!`, `report_with!`, `report_msg!`, `bail!`, `ensure!` and `aggregate!` macros,
+//! * `Report`, `Frame` and `Node`, plus the `Display` adapters `ReportDisplay`, `FilteredDisplay` and `WrappedDisplay`,
+//! * the owned `ReportSnapshot` and `OwnedFrame`, and the `Send + Sync` `SyncReport`,
+//! * `AggregateError`, the leaf built by `aggregate!`,
+//! * `AnyhowError` and `EyreError` when the `anyhow` and `eyre` features are enabled.
+//!
+//! The `ToReport`, `OptionToReport` and `ReportContext` traits are not part of it, since `prepare!` generates them inside your crate.
+//! `prepare!` also generates a `Report<T>` alias, which shadows the glob imported `Report` in the module it is invoked in;
+//! refer to the struct as `erreport::Report` there.
+
+#[cfg(feature = "anyhow")]
+pub use crate::AnyhowError;
+#[cfg(feature = "eyre")]
+pub use crate::EyreError;
+pub use crate::{
+    aggregate, bail, ensure, prepare, report, report_msg, report_with, AggregateError,
+    FilteredDisplay, Frame, Node, OwnedFrame, Report, ReportDisplay, ReportSnapshot, SyncReport,
+    WrappedDisplay,
+};
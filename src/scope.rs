@@ -0,0 +1,133 @@
+use alloc::{string::String, vec::Vec};
+use core::cell::RefCell;
+use core::fmt::Display;
+use core::marker::PhantomData;
+
+use crate::Report;
+
+std::thread_local! {
+    static CONTEXT: RefCell<Vec<(&'static str, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pushes a key-value pair onto this thread's context stack, which every report created on this thread
+/// attaches to its frame as `meta` until it is popped, rendered like `src/lib.rs:42:5 {request_id=abc}`.
+///
+/// Prefer `context_scope`, which pops it again even on an early return or a panic.
+///
+/// `value` is formatted before the stack is borrowed, so its `Display` may report errors itself:
+///
+/// ```
+/// # erreport::prepare!();
+/// struct Port;
+///
+/// impl std::fmt::Display for Port {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         let report = "x".parse::<u16>().report().unwrap_err();
+///         write!(f, "{}", report.depth())
+///     }
+/// }
+///
+/// erreport::push_context("port", Port);
+/// assert!("x".parse::<u8>().report().unwrap_err().to_string().contains(" {port=1} -> "));
+/// ```
+///
+/// During thread teardown, once the stack is gone, nothing is pushed.
+pub fn push_context(key: &'static str, value: impl Display) {
+    let value = alloc::format!("{}", value);
+    let _ = CONTEXT.try_with(|context| context.borrow_mut().push((key, value)));
+}
+
+/// Pops the last pair pushed by `push_context`, if any
+pub fn pop_context() {
+    let _ = CONTEXT.try_with(|context| context.borrow_mut().pop());
+}
+
+/// Pushes a key-value pair onto this thread's context stack for as long as the returned guard lives.
+///
+/// Reports created in the meantime carry it in their `meta`, so errors pick up ambient context, like a request id,
+/// without threading it through every call:
+///
+/// ```
+/// # erreport::prepare!();
+/// fn handle() -> Report<u8> {
+///     let _guard = erreport::context_scope("request_id", "abc");
+///     "x".parse::<u8>().report()
+/// }
+///
+/// assert!(handle().unwrap_err().to_string().contains(" {request_id=abc} -> "));
+/// assert!(!"x".parse::<u8>().report().unwrap_err().to_string().contains("request_id"));
+/// ```
+///
+/// A pair is only attached to the innermost frame carrying it, so a chain raised within one scope shows it once.
+pub fn context_scope(key: &'static str, value: impl Display) -> ContextGuard {
+    let len = CONTEXT
+        .try_with(|context| context.borrow().len())
+        .unwrap_or(0);
+    push_context(key, value);
+    ContextGuard {
+        len,
+        _not_send: PhantomData,
+    }
+}
+
+/// Labels the reports created while the returned guard lives with what was being done, the error path
+/// counterpart of a `tracing` span. It is `context_scope("scope", label)`, so nested scopes accumulate in order:
+///
+/// ```
+/// # erreport::prepare!();
+/// fn parse_header(s: &str) -> Report<u8> {
+///     let _scope = erreport::scope("parsing header");
+///     let value = s.parse::<u8>().report()?;
+///     Ok(value)
+/// }
+///
+/// fn handle(s: &str) -> Report<u8> {
+///     let _scope = erreport::scope("request");
+///     parse_header(s).report()
+/// }
+///
+/// let report = handle("x").unwrap_err();
+/// assert!(report.to_string().contains(" {scope=request scope=parsing header} -> "));
+/// // Both guards were dropped by the early returns
+/// assert!(!"x".parse::<u8>().report().unwrap_err().to_string().contains("scope="));
+/// ```
+pub fn scope(label: impl Display) -> ContextGuard {
+    context_scope("scope", label)
+}
+
+/// Pops the context pushed by `context_scope` when dropped, along with anything pushed after it and not popped yet
+#[must_use = "the context is popped as soon as the guard is dropped"]
+pub struct ContextGuard {
+    len: usize,
+    // The stack is per thread, so the guard must be dropped on the thread which created it
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        // `try_with` as the thread-local may already be gone when the guard is dropped during thread teardown
+        let _ = CONTEXT.try_with(|context| context.borrow_mut().truncate(self.len));
+    }
+}
+
+/// Appends the current context stack to the `meta` of a new frame, skipping pairs which its inner frame already has
+///
+/// A report created during thread teardown, after the stack is gone, gets no context.
+pub(crate) fn attach(report: &mut Report) {
+    let _ = CONTEXT.try_with(|context| {
+        let context = context.borrow();
+        if context.is_empty() {
+            return;
+        }
+        let inner = report
+            .err
+            .downcast_ref::<Report>()
+            .map(|inner| &inner.extra().meta);
+        for pair in context.iter() {
+            if !inner.is_some_and(|meta| meta.contains(pair)) {
+                let extra = report.extra.get_or_insert_with(Default::default);
+                extra.meta.push(pair.clone());
+            }
+        }
+    });
+}
@@ -0,0 +1,57 @@
+use alloc::{format, string::ToString, vec::Vec};
+
+use sentry_core::protocol::{Event, Exception, Frame, Level, Stacktrace};
+
+use crate::Report;
+
+impl Report {
+    /// Converts the report into a Sentry event, for `sentry::capture_event(report.to_sentry_event())`.
+    ///
+    /// The event holds a single exception whose value is the leaf error, and whose stacktrace has one frame
+    /// per `Report` frame with its `filename`, `lineno` and `colno`, `module` set to the package name and `package`
+    /// to `name@version`. As Sentry expects, the innermost frame comes last.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let event = report.to_sentry_event();
+    ///
+    /// let exception = &event.exception.values[0];
+    /// assert_eq!(exception.value.as_deref(), Some("boom"));
+    /// let frames = &exception.stacktrace.as_ref().unwrap().frames;
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!(frames[0].filename.as_deref(), Some("src/main.rs"));
+    /// assert_eq!(frames[1].filename.as_deref(), Some("src/lib.rs"));
+    /// assert_eq!(frames[1].module.as_deref(), Some("dep"));
+    /// ```
+    pub fn to_sentry_event(&self) -> Event<'static> {
+        let frames: Vec<Frame> = self
+            .frames()
+            .map(|frame| Frame {
+                filename: Some(frame.file.into()),
+                abs_path: frame.root.map(|root| format!("{}/{}", root, frame.file)),
+                lineno: Some(frame.line.into()),
+                colno: Some(frame.column.into()).filter(|&column| column != 0),
+                module: Some(frame.pkg_name.into()),
+                package: Some(format!("{}@{}", frame.pkg_name, frame.pkg_version)),
+                ..Frame::default()
+            })
+            .collect();
+        let exception = Exception {
+            ty: self.code().unwrap_or("Report").into(),
+            value: Some(self.root_cause().to_string()),
+            stacktrace: Some(Stacktrace {
+                frames,
+                ..Stacktrace::default()
+            }),
+            ..Exception::default()
+        };
+        Event {
+            level: Level::Error,
+            exception: alloc::vec![exception].into(),
+            ..Event::default()
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use slog::{Record, Serializer, KV};
+
+use crate::Report;
+
+/// The structured `slog` key-values of a `Report`, created by `Report::to_slog_kv`
+pub struct SlogKv<'a>(&'a Report);
+
+impl KV for SlogKv<'_> {
+    fn serialize(&self, _record: &Record<'_>, serializer: &mut dyn Serializer) -> slog::Result {
+        let report = self.0;
+        serializer.emit_str("err.pkg", report.pkg_name)?;
+        serializer.emit_str("err.file", report.file)?;
+        serializer.emit_u32("err.line", report.line)?;
+        serializer.emit_arguments("err.cause", &format_args!("{}", report.root_cause()))
+    }
+}
+
+impl Report {
+    /// The outermost frame and the leaf error as `slog` key-values: `err.pkg`, `err.file`, `err.line` and `err.cause`.
+    ///
+    /// ```
+    /// # erreport::prepare!();
+    /// fn log_failure(logger: &slog::Logger, report: &erreport::Report) {
+    ///     slog::error!(logger, "request failed"; report.to_slog_kv());
+    /// }
+    ///
+    /// let logger = slog::Logger::root(slog::Discard, slog::o!());
+    /// log_failure(&logger, &"x".parse::<u8>().report().unwrap_err());
+    /// ```
+    pub fn to_slog_kv(&self) -> SlogKv<'_> {
+        SlogKv(self)
+    }
+}
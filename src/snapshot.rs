@@ -0,0 +1,169 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Debug, Display, Formatter, Result};
+
+use crate::{display, Frame, FrameThread, Report};
+
+/// An owned copy of a `Frame`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OwnedFrame {
+    pub pkg_name: String,
+    pub pkg_version: String,
+    pub file: String,
+    pub root: Option<String>,
+    pub line: u32,
+    pub column: u32,
+    pub context: Option<String>,
+    pub code: Option<String>,
+    pub meta: Vec<(&'static str, String)>,
+    pub module: Option<String>,
+    #[cfg(feature = "time")]
+    pub timestamp: std::time::SystemTime,
+    pub thread: Option<String>,
+}
+
+impl OwnedFrame {
+    pub(crate) fn as_frame(&self) -> Frame<'_> {
+        Frame {
+            pkg_name: &self.pkg_name,
+            pkg_version: &self.pkg_version,
+            file: &self.file,
+            root: self.root.as_deref(),
+            line: self.line,
+            column: self.column,
+            context: self.context.as_deref(),
+            code: self.code.as_deref(),
+            meta: &self.meta,
+            module: self.module.as_deref(),
+            #[cfg(feature = "time")]
+            timestamp: self.timestamp,
+            thread: self.thread.as_deref().map(FrameThread::Name),
+        }
+    }
+}
+
+impl From<Frame<'_>> for OwnedFrame {
+    fn from(frame: Frame<'_>) -> Self {
+        OwnedFrame {
+            pkg_name: frame.pkg_name.into(),
+            pkg_version: frame.pkg_version.into(),
+            file: frame.file.into(),
+            root: frame.root.map(Into::into),
+            line: frame.line,
+            column: frame.column,
+            context: frame.context.map(Into::into),
+            code: frame.code.map(Into::into),
+            meta: frame.meta.to_vec(),
+            module: frame.module.map(Into::into),
+            #[cfg(feature = "time")]
+            timestamp: frame.timestamp,
+            thread: frame.thread.map(|thread| thread.to_string()),
+        }
+    }
+}
+
+/// A detached copy of a `Report`, created by `Report::snapshot`.
+///
+/// It owns everything it needs, so unlike `Report` it is `Clone + Send + Sync + 'static`
+/// and can be queued or sent to another thread. It renders exactly like the `Report` it was taken from:
+///
+/// ```
+/// # erreport::prepare!();
+/// let report = "x".parse::<u8>().report_with("parsing").report().unwrap_err();
+/// let snapshot = report.snapshot();
+/// assert_eq!(snapshot.to_string(), report.to_string());
+/// assert_eq!(format!("{:?}", snapshot), format!("{:?}", report));
+/// assert_eq!(snapshot.cause, "invalid digit found in string");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReportSnapshot {
+    pub frames: Vec<OwnedFrame>,
+    /// The `Display` of the leaf error, or the `Report::message` if there is one
+    pub cause: String,
+    /// The `Debug` of the leaf error
+    pub cause_debug: String,
+}
+
+impl Report {
+    /// Collects owned copies of the frames, outermost first.
+    ///
+    /// The leaf error is not a frame, get its message from `root_cause()`, or use `snapshot()` to keep both.
+    ///
+    /// ```
+    /// use erreport::Report;
+    ///
+    /// let report = Report::new("dep", "1.2.0", "src/lib.rs", 7, "boom");
+    /// let report = Report::new("app", "0.1.0", "src/main.rs", 10, report);
+    /// let frames = report.to_frames_vec();
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!((frames[0].pkg_name.as_str(), frames[0].file.as_str(), frames[0].line), ("app", "src/main.rs", 10));
+    /// assert_eq!((frames[1].pkg_name.as_str(), frames[1].file.as_str(), frames[1].line), ("dep", "src/lib.rs", 7));
+    /// assert_eq!(frames[1].pkg_version, "1.2.0");
+    /// ```
+    pub fn to_frames_vec(&self) -> Vec<OwnedFrame> {
+        self.frames().map(OwnedFrame::from).collect()
+    }
+
+    /// Takes an owned copy of the frames and the rendered leaf error
+    pub fn snapshot(&self) -> ReportSnapshot {
+        ReportSnapshot {
+            frames: self.to_frames_vec(),
+            cause: display::Leaf(self).to_string(),
+            cause_debug: format!("{:?}", display::LeafDebug(self)),
+        }
+    }
+}
+
+impl Debug for ReportSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        display::write_chain(
+            f,
+            self.frames.iter().map(OwnedFrame::as_frame),
+            &display::Style::DEFAULT,
+            |f| f.write_str(&self.cause_debug),
+        )
+    }
+}
+impl Display for ReportSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        display::write_chain(
+            f,
+            self.frames.iter().map(OwnedFrame::as_frame),
+            &display::Style::DEFAULT,
+            |f| f.write_str(&self.cause),
+        )
+    }
+}
+
+impl core::error::Error for ReportSnapshot {}
+
+/// Wraps a snapshot of the report as the inner error, with `ErrorKind::Other`, to return a `Report` from an `io::Result` function.
+///
+/// `io::Error` only holds `Send + Sync` errors, which `Report` is not, so the chain is kept as a `ReportSnapshot`:
+///
+/// ```
+/// # erreport::prepare!();
+/// use erreport::ReportSnapshot;
+///
+/// fn read_port() -> std::io::Result<u16> {
+///     Ok("x".parse::<u16>().report()?)
+/// }
+///
+/// let err = read_port().unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::Other);
+/// let snapshot = err.get_ref().unwrap().downcast_ref::<ReportSnapshot>().unwrap();
+/// assert_eq!(snapshot.cause, "invalid digit found in string");
+/// assert_eq!(snapshot.frames.len(), 1);
+/// ```
+///
+/// A `SyncReport` converts without the snapshot, and is recovered with `downcast_ref::<SyncReport>()`.
+#[cfg(feature = "std")]
+impl From<Report> for std::io::Error {
+    fn from(report: Report) -> Self {
+        std::io::Error::other(report.snapshot())
+    }
+}
@@ -0,0 +1,92 @@
+use core::error::Error;
+use core::fmt::{Debug, Display, Formatter, Result};
+use core::ops::Deref;
+
+use crate::Report;
+
+/// A `Report` which is `Send + Sync`, created by `report_sync`, e.g. to return it from a `tokio::spawn` task.
+///
+/// `report_sync` only accepts errors which are `Send + Sync` themselves, and so does every frame below it.
+/// It derefs to `Report` for rendering and inspection, and converts into one with `?` where a `Report` is expected.
+/// Reporting a `Result<T, SyncReport>` again, with `report` or `report_sync`, keeps the frames in one chain.
+///
+/// Use `Report` unless the error has to cross threads, it accepts any error.
+///
+/// ```
+/// # erreport::prepare!();
+/// let report = "x".parse::<u8>().report_sync().unwrap_err();
+/// let rendered = report.to_string();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| assert_eq!(report.to_string(), rendered));
+/// });
+/// let report = std::thread::spawn(move || report).join().unwrap();
+/// assert_eq!(report.to_string(), rendered);
+/// assert!(rendered.ends_with(" -> invalid digit found in string"));
+/// ```
+pub struct SyncReport(pub(crate) Report);
+
+// SAFETY: `Report` is only `!Send + !Sync` because of its `err: Box<dyn Error>`. A `SyncReport` is only built by
+// `__private::capture_sync`, whose `err` is a `Box<dyn Error + Send + Sync>`: either the leaf, or another `SyncReport`
+// whose chain holds by the same argument. The wrapped `Report` is never handed out by `&mut`, so its `err` can't be
+// replaced by one which isn't `Send + Sync`. That every other field is `Send + Sync` is checked below.
+unsafe impl Send for SyncReport {}
+unsafe impl Sync for SyncReport {}
+
+const fn assert_send_sync<T: Send + Sync>() {}
+
+// Every field of `Report` but `err`, so adding one which isn't `Send + Sync` fails the build instead of being unsound
+const _: () = {
+    assert_send_sync::<&'static str>();
+    assert_send_sync::<Option<&'static core::panic::Location<'static>>>();
+    #[cfg(feature = "time")]
+    assert_send_sync::<std::time::SystemTime>();
+    #[cfg(feature = "std")]
+    assert_send_sync::<Option<std::thread::Thread>>();
+    assert_send_sync::<Option<alloc::boxed::Box<crate::ReportExtra>>>();
+    assert_send_sync::<SyncReport>();
+};
+
+impl SyncReport {
+    pub fn into_report(self) -> Report {
+        self.0
+    }
+}
+
+impl Deref for SyncReport {
+    type Target = Report;
+
+    fn deref(&self) -> &Report {
+        &self.0
+    }
+}
+
+impl From<SyncReport> for Report {
+    fn from(report: SyncReport) -> Self {
+        report.0
+    }
+}
+
+impl Debug for SyncReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+impl Display for SyncReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.0, f)
+    }
+}
+impl Error for SyncReport {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Wraps the report itself as the inner error, with `ErrorKind::Other`, so `downcast_ref::<SyncReport>()` gives back the whole chain
+#[cfg(feature = "std")]
+impl From<SyncReport> for std::io::Error {
+    fn from(report: SyncReport) -> Self {
+        std::io::Error::other(report)
+    }
+}
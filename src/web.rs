@@ -0,0 +1,100 @@
+use alloc::string::ToString;
+
+use crate::{display, Report, SyncReport};
+
+impl Report {
+    /// The HTTP status a web framework responds with for this report: the `code()` if it is a client or server error
+    /// status, like `"404"`, otherwise `500`
+    pub fn http_status(&self) -> u16 {
+        self.code()
+            .and_then(|code| code.parse::<u16>().ok())
+            .filter(|status| (400..=599).contains(status))
+            .unwrap_or(500)
+    }
+}
+
+/// Logs the whole chain on the server side, as only the leaf error goes into the response
+fn log_report(report: &Report) {
+    #[cfg(feature = "tracing")]
+    crate::__private::trace_report(report);
+    #[cfg(all(feature = "log", not(feature = "tracing")))]
+    crate::__private::log_report(report, log::Level::Error);
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    report.eprint();
+}
+
+/// Responds with `Report::http_status` and the leaf error, or the `report_as` message, as a plain text body.
+///
+/// The frames stay out of the response, they are logged instead: as a `tracing` event or a `log` record when
+/// those features are enabled, otherwise to stderr.
+///
+/// ```
+/// # erreport::prepare!();
+/// use axum_core::response::IntoResponse;
+///
+/// let report = "x".parse::<u8>().report_coded("400").unwrap_err();
+/// let response = report.into_response();
+/// assert_eq!(response.status(), 400);
+/// ```
+///
+/// `Report` isn't `Send`, so a handler mustn't hold one across an `.await`; `SyncReport` can be returned the same way.
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for Report {
+    fn into_response(self) -> axum_core::response::Response {
+        log_report(&self);
+        let status = http::StatusCode::from_u16(self.http_status())
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        (status, display::Leaf(&self).to_string()).into_response()
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for SyncReport {
+    fn into_response(self) -> axum_core::response::Response {
+        self.into_report().into_response()
+    }
+}
+
+/// Responds like the `axum` integration: `Report::http_status` with only the leaf error, or the `report_as` message,
+/// as a plain text body, while the whole chain is logged.
+///
+/// ```
+/// # erreport::prepare!();
+/// use actix_web::{web, App, ResponseError};
+///
+/// async fn port(value: web::Path<String>) -> Result<String, erreport::Report> {
+///     let port = value.parse::<u16>().report_coded("400")?;
+///     Ok(port.to_string())
+/// }
+///
+/// let _app = App::new().route("/port/{value}", web::get().to(port));
+///
+/// let report = "x".parse::<u16>().report_coded("400").unwrap_err();
+/// assert_eq!(report.status_code(), 400);
+/// assert_eq!(report.error_response().status(), 400);
+/// ```
+#[cfg(feature = "actix")]
+impl actix_web::ResponseError for Report {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::from_u16(self.http_status())
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        log_report(self);
+        actix_web::HttpResponse::build(self.status_code())
+            .content_type("text/plain; charset=utf-8")
+            .body(display::Leaf(self).to_string())
+    }
+}
+
+#[cfg(feature = "actix")]
+impl actix_web::ResponseError for SyncReport {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::ResponseError::status_code(&**self)
+    }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        actix_web::ResponseError::error_response(&**self)
+    }
+}